@@ -0,0 +1,128 @@
+//! Pseudonyms that stand in for artist ids in printed output, for grading a
+//! log without knowing whose run it was.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::RwLock;
+
+use crate::canvas::Artist;
+use crate::color::fnv1a;
+
+/// A stable pseudonym (`A`, `B`, ..., `Z`, `AA`, ...) for every artist in a
+/// log. Letters are handed out in the order of a hash of each id rather than
+/// the ids themselves, so the pseudonyms don't give away which artist
+/// started first, but the same log always gets the same ones.
+#[derive(Debug, Clone, Default)]
+pub struct Pseudonyms {
+    names: HashMap<Artist, String>,
+}
+
+impl Pseudonyms {
+    pub fn new(artists: impl IntoIterator<Item = Artist>) -> Pseudonyms {
+        let mut artists: Vec<Artist> = artists.into_iter().collect();
+        artists.sort_unstable();
+        artists.dedup();
+        artists.sort_by_key(|artist| (fnv1a(&artist.to_le_bytes()), *artist));
+        let names = artists
+            .into_iter()
+            .enumerate()
+            .map(|(i, artist)| (artist, letters(i)))
+            .collect();
+        Pseudonyms { names }
+    }
+
+    /// The artist's pseudonym, if it was in the log.
+    pub fn get(&self, artist: Artist) -> Option<&str> {
+        self.names.get(&artist).map(String::as_str)
+    }
+
+    /// The artist with this pseudonym, if there is one.
+    pub fn artist(&self, pseudonym: &str) -> Option<Artist> {
+        self.names
+            .iter()
+            .find(|(_, name)| name.as_str() == pseudonym)
+            .map(|(artist, _)| *artist)
+    }
+
+    /// Writes one `pseudonym id` line per artist, in pseudonym order.
+    pub fn write_mapping(&self, out: &mut impl Write) -> io::Result<()> {
+        let mut names: Vec<(&String, &Artist)> =
+            self.names.iter().map(|(id, name)| (name, id)).collect();
+        names.sort_by_key(|(name, _)| (name.len(), name.as_str()));
+        for (name, id) in names {
+            writeln!(out, "{} {}", name, id)?;
+        }
+        Ok(())
+    }
+}
+
+// set by --anonymize for each log, before anything about its artists is printed
+static INSTALLED: RwLock<Option<Pseudonyms>> = RwLock::new(None);
+
+fn installed() -> std::sync::RwLockReadGuard<'static, Option<Pseudonyms>> {
+    INSTALLED
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Makes every artist printed through `name` show up as its pseudonym from
+/// here on, replacing any pseudonyms installed before.
+pub fn install(pseudonyms: Pseudonyms) {
+    let mut installed = INSTALLED
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *installed = Some(pseudonyms);
+}
+
+/// Whether pseudonyms have been installed.
+pub fn enabled() -> bool {
+    installed().is_some()
+}
+
+/// How an artist should be printed: its pseudonym once pseudonyms are
+/// installed, otherwise its id.
+pub fn name(artist: Artist) -> Name {
+    Name(artist)
+}
+
+/// The artist that `text` names: its pseudonym once pseudonyms are
+/// installed, otherwise its id.
+pub fn parse_name(text: &str) -> Option<Artist> {
+    match installed().as_ref() {
+        Some(pseudonyms) => pseudonyms.artist(text),
+        None => text.parse().ok(),
+    }
+}
+
+/// An artist as printed; see `name`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Name(Artist);
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match installed().as_ref() {
+            Some(pseudonyms) => match pseudonyms.get(self.0) {
+                Some(pseudonym) => f.write_str(pseudonym),
+                // artists outside the log, like ones that were expected but
+                // never painted, have no pseudonym to hide behind
+                None => f.write_str("(unnamed)"),
+            },
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
+
+// 0 -> A, 25 -> Z, 26 -> AA, like spreadsheet columns
+fn letters(mut index: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap()
+}
@@ -0,0 +1,87 @@
+//! A compact binary form of a parsed canvas, for reloading large logs without
+//! parsing their text again.
+//!
+//! The file starts with the 4 byte magic `CVLB` and a 1 byte format version,
+//! then the pixel count as a little-endian `u64`, then one 24 byte record per
+//! pixel in paint order: artist `u64`, x `i16`, y `i16`, r, g, b `u8`, a `u8`
+//! that is 1 when a timestamp follows, and the timestamp `u64` (0 when absent).
+//! Everything is little-endian.
+
+use std::convert::TryInto;
+use std::io::{self, BufRead, Read, Write};
+
+use crate::canvas::{Canvas, CanvasPixel};
+
+const MAGIC: &[u8; 4] = b"CVLB";
+
+/// Bumped whenever the record layout changes.
+pub const FORMAT_VERSION: u8 = 1;
+
+const RECORD_LEN: usize = 24;
+
+/// Writes the canvas in the binary format.
+pub fn write_binary(canvas: &Canvas, out: &mut impl Write) -> io::Result<()> {
+    out.write_all(MAGIC)?;
+    out.write_all(&[FORMAT_VERSION])?;
+    out.write_all(&(canvas.len() as u64).to_le_bytes())?;
+    for pixel in canvas {
+        let mut record = [0u8; RECORD_LEN];
+        record[0..8].copy_from_slice(&pixel.artist.to_le_bytes());
+        record[8..10].copy_from_slice(&pixel.coord.x.to_le_bytes());
+        record[10..12].copy_from_slice(&pixel.coord.y.to_le_bytes());
+        record[12] = pixel.color.r;
+        record[13] = pixel.color.g;
+        record[14] = pixel.color.b;
+        if let Some(timestamp) = pixel.timestamp {
+            record[15] = 1;
+            record[16..24].copy_from_slice(&timestamp.to_le_bytes());
+        }
+        out.write_all(&record)?;
+    }
+    Ok(())
+}
+
+/// Whether the reader starts with the binary format's magic, without
+/// consuming anything.
+pub fn is_binary(reader: &mut impl BufRead) -> io::Result<bool> {
+    Ok(reader.fill_buf()?.starts_with(MAGIC))
+}
+
+/// Reads a canvas written by `write_binary`. Sequence numbers are reassigned
+/// from the record order, which is the order they were saved in.
+pub fn read_binary(reader: &mut impl Read) -> io::Result<Canvas> {
+    let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+    let mut header = [0u8; 13];
+    reader.read_exact(&mut header)?;
+    if &header[0..4] != MAGIC {
+        return Err(invalid("not a binary canvas file".to_string()));
+    }
+    if header[4] != FORMAT_VERSION {
+        return Err(invalid(format!(
+            "binary canvas format version {} is not supported; expected {}",
+            header[4], FORMAT_VERSION
+        )));
+    }
+    let count = u64::from_le_bytes(header[5..13].try_into().unwrap());
+
+    let mut pixels = Vec::new();
+    let mut record = [0u8; RECORD_LEN];
+    for seq in 0..count as usize {
+        reader.read_exact(&mut record)?;
+        let mut pixel = CanvasPixel::new(
+            u64::from_le_bytes(record[0..8].try_into().unwrap()),
+            i16::from_le_bytes(record[8..10].try_into().unwrap()),
+            i16::from_le_bytes(record[10..12].try_into().unwrap()),
+            record[12],
+            record[13],
+            record[14],
+        );
+        if record[15] == 1 {
+            pixel.timestamp = Some(u64::from_le_bytes(record[16..24].try_into().unwrap()));
+        }
+        pixel.seq = seq;
+        pixels.push(pixel);
+    }
+    Ok(Canvas::new(pixels))
+}
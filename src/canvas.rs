@@ -0,0 +1,310 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::iter::FromIterator;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::color::Color;
+use crate::parse::{read_log_to_canvas, LogError, ParseOptions};
+
+/// An artist's thread id. Wide enough for 64-bit thread ids.
+pub type Artist = u64;
+
+/// Every pixel in a log, in the order the log lists them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Canvas {
+    pixels: Vec<CanvasPixel>,
+}
+
+impl Canvas {
+    pub fn new(pixels: Vec<CanvasPixel>) -> Canvas {
+        Canvas { pixels }
+    }
+
+    /// Opens and parses the log at `path`, stopping at the first malformed line.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Canvas, LogError> {
+        let file = File::open(path)?;
+        Canvas::from_reader(BufReader::new(file))
+    }
+
+    /// Parses a log from any buffered reader, stopping at the first malformed line.
+    ///
+    /// ```
+    /// use log_reader::Canvas;
+    ///
+    /// let log = "5000000000, 1, 2, 255, 0, 0\n";
+    /// let canvas = Canvas::from_reader(log.as_bytes()).unwrap();
+    /// assert_eq!(canvas.pixels()[0].artist, 5_000_000_000);
+    /// ```
+    pub fn from_reader(reader: impl BufRead) -> Result<Canvas, LogError> {
+        let (canvas, mut errors) = read_log_to_canvas(reader, &ParseOptions::default())?;
+        match errors.pop() {
+            Some(error) => Err(LogError::Parse(error)),
+            None => Ok(canvas),
+        }
+    }
+
+    pub fn pixels(&self) -> &[CanvasPixel] {
+        &self.pixels
+    }
+
+    pub fn len(&self) -> usize {
+        self.pixels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pixels.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, CanvasPixel> {
+        self.pixels.iter()
+    }
+
+    /// The box containing every painted pixel, or `None` for an empty canvas.
+    pub fn bounds(&self) -> Option<BoundingBox> {
+        let mut pixels = self.pixels.iter();
+        let mut bounds = BoundingBox::new(pixels.next()?.coord);
+        for pixel in pixels {
+            bounds.include(pixel.coord);
+        }
+        Some(bounds)
+    }
+
+    /// Shifts the canvas so its bounding box starts at `(0, 0)`, returning the
+    /// shifted canvas and the offset that was subtracted from every point, the
+    /// box's old top-left corner. Exporters should go through this rather than
+    /// shifting on their own. Points more than `i16::MAX` past the corner don't
+    /// fit and are clamped to it, so exporters should refuse canvases spanning
+    /// more than 32768 cells either way first.
+    pub fn normalized_to_origin(&self) -> (Canvas, Point) {
+        let offset = match self.bounds() {
+            Some(bounds) => bounds.min,
+            None => Point::new(0, 0),
+        };
+        let shift = |value: i16, by: i16| (value as i32 - by as i32).min(i16::MAX as i32) as i16;
+        let shifted = self
+            .pixels
+            .iter()
+            .map(|pixel| {
+                let mut shifted = *pixel;
+                shifted.coord = Point::new(
+                    shift(pixel.coord.x, offset.x),
+                    shift(pixel.coord.y, offset.y),
+                );
+                shifted
+            })
+            .collect();
+        (shifted, offset)
+    }
+}
+
+impl FromIterator<CanvasPixel> for Canvas {
+    fn from_iter<I: IntoIterator<Item = CanvasPixel>>(iter: I) -> Canvas {
+        Canvas::new(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for Canvas {
+    type Item = CanvasPixel;
+    type IntoIter = std::vec::IntoIter<CanvasPixel>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.pixels.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Canvas {
+    type Item = &'a CanvasPixel;
+    type IntoIter = std::slice::Iter<'a, CanvasPixel>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.pixels.iter()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct CanvasPixel {
+    pub artist: Artist,
+    pub coord: Point,
+    pub color: Color,
+    /// When the pixel was painted, for logs written with a timestamp column.
+    pub timestamp: Option<u64>,
+    /// Where the pixel falls in paint order: its position among the pixels
+    /// parsed from the log, starting at 0.
+    pub seq: usize,
+}
+
+impl CanvasPixel {
+    pub fn new(artist: Artist, x: i16, y: i16, red: u8, green: u8, blue: u8) -> CanvasPixel {
+        CanvasPixel {
+            artist,
+            coord: Point::new(x, y),
+            color: Color::new(red, green, blue),
+            timestamp: None,
+            seq: 0,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, Serialize)]
+pub struct Point {
+    pub x: i16,
+    pub y: i16,
+}
+
+impl Point {
+    pub fn new(x: i16, y: i16) -> Point {
+        Point { x, y }
+    }
+
+    /// Steps between the two points moving only along rows and columns,
+    /// `|dx| + |dy|`. The differences are taken in `i32`, so points at
+    /// opposite ends of the coordinate range don't overflow.
+    ///
+    /// ```
+    /// use log_reader::Point;
+    ///
+    /// assert_eq!(Point::new(1, 2).manhattan_distance(&Point::new(4, -2)), 7);
+    /// assert_eq!(
+    ///     Point::new(i16::MIN, i16::MIN).manhattan_distance(&Point::new(i16::MAX, i16::MAX)),
+    ///     2 * 65535
+    /// );
+    /// ```
+    pub fn manhattan_distance(&self, other: &Point) -> u32 {
+        let dx = (self.x as i32 - other.x as i32).unsigned_abs();
+        let dy = (self.y as i32 - other.y as i32).unsigned_abs();
+        dx + dy
+    }
+
+    /// Straight-line distance between the two points.
+    ///
+    /// ```
+    /// use log_reader::Point;
+    ///
+    /// assert_eq!(Point::new(0, 0).euclidean_distance(&Point::new(3, 4)), 5.0);
+    /// assert_eq!(Point::new(-2, 7).euclidean_distance(&Point::new(-2, 7)), 0.0);
+    /// ```
+    pub fn euclidean_distance(&self, other: &Point) -> f64 {
+        let dx = self.x as f64 - other.x as f64;
+        let dy = self.y as f64 - other.y as f64;
+        dx.hypot(dy)
+    }
+
+    /// The four points sharing an edge with this one.
+    pub fn neighbors(&self) -> [Point; 4] {
+        [
+            Point::new(self.x.wrapping_sub(1), self.y),
+            Point::new(self.x.wrapping_add(1), self.y),
+            Point::new(self.x, self.y.wrapping_sub(1)),
+            Point::new(self.x, self.y.wrapping_add(1)),
+        ]
+    }
+}
+
+/// The smallest axis-aligned box containing a set of points, inclusive on both ends.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl BoundingBox {
+    /// A box around just `point`.
+    pub fn new(point: Point) -> BoundingBox {
+        BoundingBox {
+            min: point,
+            max: point,
+        }
+    }
+
+    /// Grows the box to contain `point`.
+    pub fn include(&mut self, point: Point) {
+        self.min.x = self.min.x.min(point.x);
+        self.min.y = self.min.y.min(point.y);
+        self.max.x = self.max.x.max(point.x);
+        self.max.y = self.max.y.max(point.y);
+    }
+
+    /// Whether the two boxes share at least one cell.
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
+    pub fn width(&self) -> u32 {
+        (self.max.x as i32 - self.min.x as i32 + 1) as u32
+    }
+
+    pub fn height(&self) -> u32 {
+        (self.max.y as i32 - self.min.y as i32 + 1) as u32
+    }
+}
+
+/// Which corner of the canvas a log's `(0, 0)` is in. Renders are always drawn
+/// top row first, so bottom-left logs get their y flipped on the way out.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Origin {
+    /// y grows downward, as in image formats. The default.
+    #[default]
+    TopLeft,
+    /// y grows upward, as in math plots.
+    BottomLeft,
+}
+
+impl Origin {
+    /// Where `point` lands in top-left coordinates. Bottom-left logs are
+    /// flipped within `bounds`, so the result never leaves the box.
+    pub fn to_top_left(&self, point: Point, bounds: &BoundingBox) -> Point {
+        match self {
+            Origin::TopLeft => point,
+            Origin::BottomLeft => Point::new(
+                point.x,
+                (bounds.max.y as i32 - (point.y as i32 - bounds.min.y as i32)) as i16,
+            ),
+        }
+    }
+}
+
+impl FromStr for Origin {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top-left" => Ok(Origin::TopLeft),
+            "bottom-left" => Ok(Origin::BottomLeft),
+            _ => Err(format!(
+                "Unknown origin {}; expected top-left or bottom-left",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for BoundingBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({}, {}) to ({}, {})",
+            self.min.x, self.min.y, self.max.x, self.max.y
+        )
+    }
+}
+
+// similarly here, ordering by rightmost point
+impl PartialOrd for Point {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// trick here: we just care about *some* relative ordering, so ordering by rightmost point.
+impl Ord for Point {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.x.cmp(&other.x)
+    }
+}
@@ -0,0 +1,1812 @@
+use std::cell::Cell;
+use std::cmp::{max, min};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use crate::anon::name;
+use crate::canvas::{Artist, BoundingBox, Canvas, CanvasPixel, Point};
+use crate::color::{Color, ColorDerivation, ColorMetric, Deficiency};
+use crate::patterns::{lattice_period, shape, Shape, Transform};
+use crate::regions::{convex_hull, holes, hull_distance, largest_cluster, pieces};
+use crate::stats::coverage;
+use crate::term::{deterministic, green, red, take_warnings};
+use crate::{say, warn};
+
+thread_local! {
+    // offenders of the check `CheckResults::run` is running, once any
+    // list of them has finished
+    static TALLY: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Prints offenders for a check up to an optional limit, while still counting
+/// the ones it hides so the check can say how many were left out.
+pub struct OffenderList {
+    limit: Option<usize>,
+    count: usize,
+    // whether `finish` adds the count to the running check's offenders
+    tallied: bool,
+}
+
+impl OffenderList {
+    pub fn new(limit: Option<usize>) -> OffenderList {
+        OffenderList {
+            limit,
+            count: 0,
+            tallied: true,
+        }
+    }
+
+    /// A list of details about one offender, which aren't offenders of the
+    /// check themselves.
+    pub fn nested(limit: Option<usize>) -> OffenderList {
+        OffenderList {
+            tallied: false,
+            ..OffenderList::new(limit)
+        }
+    }
+
+    /// Records one offender, returning whether it should be printed.
+    pub fn admit(&mut self) -> bool {
+        self.count += 1;
+        match self.limit {
+            Some(limit) => self.count <= limit,
+            None => true,
+        }
+    }
+
+    /// Records `n` offenders printed together, as one range, returning whether
+    /// they should be printed: whether the first of them fits in the limit.
+    pub fn admit_run(&mut self, n: usize) -> bool {
+        self.count += n;
+        match self.limit {
+            Some(limit) => self.count - n < limit,
+            None => true,
+        }
+    }
+
+    /// How many offenders have been recorded, printed or not.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn finish(&self) {
+        if self.tallied {
+            TALLY.with(|tally| tally.set(Some(tally.get().unwrap_or(0) + self.count)));
+        }
+        if let Some(limit) = self.limit {
+            if self.count > limit {
+                eprintln!("... and {} more", self.count - limit);
+            }
+        }
+    }
+}
+
+pub fn check_enough_artists(artists: &[&Artist], num_artists: usize) -> Result<(), String> {
+    if artists.len() != num_artists {
+        Err(format!(
+            "Expected {} artists, but found {}; incorrect number of artists painted!",
+            num_artists,
+            artists.len()
+        ))
+    } else {
+        say!("Found {} artists!", num_artists);
+        Ok(())
+    }
+}
+
+/// Names of the checks a run can record, for picking which ones to run.
+pub const CHECK_NAMES: &[&str] = &[
+    "pixel-count",
+    "full",
+    "aspect",
+    "artist-count",
+    "expected-artists",
+    "contiguous-ids",
+    "tid-range",
+    "all-draw",
+    "max-pixels",
+    "color-per-artist",
+    "colors",
+    "cvd",
+    "derived-colors",
+    "grayscale",
+    "timestamps",
+    "aliasing",
+    "gaps",
+    "protected",
+    "palette",
+    "self-overlap",
+    "overlap",
+    "coincident",
+    "color-regions",
+    "outliers",
+    "holes",
+    "patterns",
+    "mirrors",
+    "shared-rng",
+    "lattice",
+    "border",
+    "double-logged",
+];
+
+/// Why the check called `name` exists: what it catches and the bug in a
+/// multithreaded simulation that usually causes it.
+pub fn explanation(name: &str) -> Option<&'static str> {
+    let why = match name {
+        "pixel-count" => "The log as a whole should hold exactly as many pixels as the simulation paints every run. Too few usually means a thread exited early, lines were lost before the log was flushed, or threads raced on a shared counter and lost updates; too many means a loop ran past its bound or a line was logged twice.",
+        "full" => "The simulation was meant to fill the whole canvas. Unpainted pixels usually mean threads gave up on positions that looked taken instead of moving on, or stopped before the work was finished.",
+        "aspect" => "The drawing should have the shape the assignment asked for. A stretched or squashed canvas usually means width and height were swapped, or one coordinate was computed with the wrong bound.",
+        "artist-count" => "Every artist thread should show up in the log. A missing artist usually means a thread was never created, never joined, or its output was lost because the log wasn't flushed before exit.",
+        "expected-artists" => "Exactly the artists you launched should appear. Unknown ids usually mean ids were read from shared memory after another thread changed them; missing ones, that a thread never started.",
+        "tid-range" => "Artist ids should be thread ids. An id of 0 or one far outside the range the OS hands out usually means the tid was logged before it was set, or the wrong variable was printed.",
+        "contiguous-ids" => "Artist ids should count up without gaps. A gap usually means two threads read the same next id from a shared counter that wasn't updated atomically.",
+        "all-draw" => "Every artist should paint something. An artist that never paints usually starved, waiting on a lock it could never get.",
+        "max-pixels" => "No artist should paint more than its share. One that does usually kept painting after it should have stopped, or was handed another thread's work.",
+        "color-per-artist" => "With one color per artist, the number of colors and artists should match. Fewer colors means artists share one; more means some artist changed color partway through.",
+        "colors" => "Each artist should have its own color. Shared colors usually come from threads seeding their random number generators with the same value, or reading a color another thread just wrote.",
+        "cvd" => "Artist colors should stay distinguishable for people with color-vision deficiencies. This is about picking colors, not a threading bug; regenerate colors that collapse together.",
+        "derived-colors" => "Each artist's color should follow from its id. A mismatch usually means a thread computed its color from another thread's id, a sign the id was shared instead of copied.",
+        "grayscale" => "Colors should be colorful. A gray color usually means the color was never randomized, or every channel was taken from the same random number.",
+        "timestamps" => "Lines should be logged in the order things happened. Out-of-order timestamps mean the timestamp was taken outside the lock that writes the line.",
+        "aliasing" => "Distinct positions shouldn't collapse onto one point. An artist repainting a point in the same color long after it first painted there usually means wide coordinates were truncated into 16 bits, so positions a multiple of 65536 apart land on the same point.",
+        "gaps" => "An artist shouldn't go quiet for much longer than usual. Long pauses in a timeline usually mean lines were dropped, often from unsynchronized writes to the log.",
+        "protected" => "Some positions are background that nobody may paint. Painting there means the thread didn't check the position before claiming it.",
+        "palette" => "Artists should pick colors from the palette. Colors outside it usually mean the index into the palette was out of range or read before being set.",
+        "self-overlap" => "An artist shouldn't paint the same pixel twice. A repaint usually means the artist lost track of where it had been, or the same line was logged twice.",
+        "overlap" => "No two artists should paint the same pixel. Overlaps mean the position wasn't locked, or was checked and then claimed without holding the lock in between.",
+        "coincident" => "No two artists should paint exactly the same pixels. Identical regions usually mean one thread's work was logged under two ids, or two threads were handed the same region.",
+        "color-regions" => "Each color should be one connected blob. A color in two places usually means two artists drew the same random color, or one artist's region broke apart because other threads painted through it.",
+        "outliers" => "Every pixel should be part of the drawing. A pixel far from everything else usually comes from a coordinate that was never set, or one that overflowed.",
+        "holes" => "Each artist should fill in its region. Holes usually mean a thread skipped positions it wrongly thought were taken.",
+        "patterns" => "Independent artists shouldn't paint the same pattern. Repeated patterns usually come from threads sharing a random number generator or seeding it with the same value.",
+        "mirrors" => "Artists shouldn't paint mirror images of each other. Mirrored patterns usually mean two threads used the same random values with a coordinate flipped.",
+        "shared-rng" => "Independent artists shouldn't move in step. Two artists taking the same long run of moves, even from different starting points, usually share one random number generator, or seed theirs with the same value.",
+        "border" => "Artists should paint their own region. An artist whose every pixel is on the canvas edge is likely tracing the border, from a loop over the edges or bounds mixed up with the region's.",
+        "lattice" => "Artists should paint contiguous shapes. Points spaced evenly on a grid usually mean a loop stepped by the thread count or pattern size instead of 1.",
+        "double-logged" => "One thread shouldn't show up under two ids. Two ids painting the same color over the same region usually mean a thread's id was read from shared memory while it changed.",
+        _ => return None,
+    };
+    Some(why)
+}
+
+/// The outcome of every check that ran, in the order they ran.
+#[derive(Debug, Default)]
+pub struct CheckResults {
+    results: Vec<(&'static str, Result<(), String>)>,
+    // offenders each check found, for the checks that list them
+    offenders: HashMap<&'static str, usize>,
+    // when set, only these checks run
+    only: Option<HashSet<&'static str>>,
+    skip: HashSet<&'static str>,
+    skipped: Vec<&'static str>,
+    // print why each check exists before running it
+    explain: bool,
+    // keep failure messages for `print_by_severity` instead of printing them
+    by_severity: bool,
+}
+
+impl CheckResults {
+    /// Results that only run the checks in `only` (all of them when `None`),
+    /// minus the ones in `skip`.
+    pub fn with_selection(
+        only: Option<HashSet<&'static str>>,
+        skip: HashSet<&'static str>,
+    ) -> CheckResults {
+        CheckResults {
+            only,
+            skip,
+            ..CheckResults::default()
+        }
+    }
+
+    /// Prints each check's `explanation` before running it, for students using
+    /// the checks to find their own bugs.
+    pub fn set_explain(&mut self, explain: bool) {
+        self.explain = explain;
+    }
+
+    /// Holds each failed check's message back for `print_by_severity`.
+    pub fn set_by_severity(&mut self, by_severity: bool) {
+        self.by_severity = by_severity;
+    }
+
+    /// Whether the check called `name` should run; a check that shouldn't is
+    /// remembered as skipped for the summary.
+    pub fn wants(&mut self, name: &'static str) -> bool {
+        let wanted =
+            self.only.as_ref().is_none_or(|only| only.contains(name)) && !self.skip.contains(name);
+        if !wanted {
+            self.skipped.push(name);
+        }
+        wanted
+    }
+
+    /// Records the outcome of the check called `name`, printing its message if it failed.
+    pub fn record(&mut self, name: &'static str, result: Result<(), String>) {
+        match &result {
+            Err(msg) if !self.by_severity => eprintln!("{}", msg),
+            _ => (),
+        }
+        self.results.push((name, result));
+    }
+
+    /// Runs the check called `name` and records its outcome, along with how
+    /// many offenders it listed.
+    pub fn run(&mut self, name: &'static str, check: impl FnOnce() -> Result<(), String>) {
+        if let Some(why) = explanation(name).filter(|_| self.explain) {
+            say!("Why {}: {}", name, why);
+        }
+        TALLY.with(|tally| tally.set(None));
+        let result = check();
+        if let Some(count) = TALLY.with(Cell::take) {
+            self.offenders.insert(name, count);
+        }
+        self.record(name, result);
+    }
+
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    pub fn failures(&self) -> usize {
+        self.failed().count()
+    }
+
+    /// Names of the checks that failed.
+    pub fn failed(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.results
+            .iter()
+            .filter(|(_, result)| result.is_err())
+            .map(|(name, _)| *name)
+    }
+
+    /// Names of the checks that were skipped.
+    pub fn skipped(&self) -> &[&'static str] {
+        &self.skipped
+    }
+
+    pub fn print_summary(&self) {
+        if !self.skipped.is_empty() {
+            say!("Skipped checks: {}", self.skipped.join(", "));
+        }
+        if self.failures() == 0 {
+            say!("{} all {} checks passed.", green("PASS:"), self.len());
+        } else {
+            let failed: Vec<&str> = self.failed().collect();
+            say!(
+                "{} {} of {} checks failed: {}",
+                red("FAIL:"),
+                failed.len(),
+                self.len(),
+                failed.join(", ")
+            );
+        }
+    }
+
+    /// Prints what the run found in three labeled sections, most severe
+    /// first: the failed checks with their messages, then every warning held
+    /// back with `term::set_hold_warnings`, then the checks that passed or
+    /// were skipped.
+    pub fn print_by_severity(&self) {
+        say!("Errors:");
+        let mut errors = 0;
+        for (name, result) in &self.results {
+            if let Err(msg) = result {
+                errors += 1;
+                say!("  {}: {}", name, msg);
+            }
+        }
+        if errors == 0 {
+            say!("  none");
+        }
+
+        say!("Warnings:");
+        let warnings = take_warnings();
+        for warning in &warnings {
+            say!("  {}", warning);
+        }
+        if warnings.is_empty() {
+            say!("  none");
+        }
+
+        say!("Info:");
+        let passed: Vec<&str> = self
+            .results
+            .iter()
+            .filter(|(_, result)| result.is_ok())
+            .map(|(name, _)| *name)
+            .collect();
+        say!(
+            "  passed: {}",
+            if passed.is_empty() {
+                "none".to_string()
+            } else {
+                passed.join(", ")
+            }
+        );
+        if !self.skipped.is_empty() {
+            say!("  skipped: {}", self.skipped.join(", "));
+        }
+    }
+
+    /// Prints one aligned row per check to stderr: its name, whether it
+    /// passed, and how many offenders it found (`-` for checks that don't
+    /// list any).
+    pub fn print_table(&self) {
+        let width = self
+            .results
+            .iter()
+            .map(|(name, _)| name.len())
+            .chain(std::iter::once("check".len()))
+            .max()
+            .unwrap_or(0);
+        eprintln!("{:<width$}  result  offenders", "check", width = width);
+        for (name, result) in &self.results {
+            let offenders = match self.offenders.get(name) {
+                Some(count) => count.to_string(),
+                None => "-".to_string(),
+            };
+            eprintln!(
+                "{:<width$}  {:<6}  {:>9}",
+                name,
+                if result.is_ok() { "PASS" } else { "FAIL" },
+                offenders,
+                width = width
+            );
+        }
+    }
+}
+
+/// Fails unless every coordinate of a `width` x `height` canvas was painted.
+pub fn check_canvas_full(canvas: &Canvas, width: usize, height: usize) -> Result<(), String> {
+    say!(
+        "Verifying that the whole {}x{} canvas was painted...",
+        width,
+        height
+    );
+    let (painted, total) = coverage(canvas, width, height);
+    if painted == total {
+        say!("Every pixel of the canvas was painted!");
+        Ok(())
+    } else {
+        Err(format!(
+            "{} of {} pixels were never painted; artists should keep going until the whole canvas is filled.",
+            total - painted,
+            total
+        ))
+    }
+}
+
+// how far, as a fraction of the expected ratio, the drawing's aspect ratio
+// may stray before it counts as the wrong shape
+const ASPECT_TOLERANCE: f64 = 0.1;
+
+/// Compares the width:height ratio of the drawing's bounding box against the
+/// expected one, allowing `ASPECT_TOLERANCE` of slack.
+pub fn check_aspect_ratio(bounds: Option<BoundingBox>, expected: f64) -> Result<(), String> {
+    say!(
+        "Verifying that the drawing's aspect ratio is about {:.2}:1...",
+        expected
+    );
+    let bounds = match bounds {
+        Some(bounds) => bounds,
+        None => return Err("Nothing was painted, so the drawing has no aspect ratio.".to_string()),
+    };
+    let found = bounds.width() as f64 / bounds.height() as f64;
+    let deviation = (found - expected) / expected;
+    if deviation.abs() <= ASPECT_TOLERANCE {
+        say!(
+            "The drawing's aspect ratio is {:.2}:1 ({:+.1}%)!",
+            found,
+            100.0 * deviation
+        );
+        Ok(())
+    } else {
+        Err(format!(
+            "The drawing is {}x{}, an aspect ratio of {:.2}:1, {:+.1}% off the expected {:.2}:1; artists may not be covering the whole canvas.",
+            bounds.width(),
+            bounds.height(),
+            found,
+            100.0 * deviation,
+            expected
+        ))
+    }
+}
+
+/// Compares the number of pixels in the log, or the number of distinct
+/// coordinates when `dedupe` is set, against a known total.
+pub fn check_pixel_count(canvas: &Canvas, expected: usize, dedupe: bool) -> Result<(), String> {
+    let (found, what) = if dedupe {
+        let coords: HashSet<Point> = canvas.iter().map(|p| p.coord).collect();
+        (coords.len(), "distinct coordinates")
+    } else {
+        (canvas.len(), "pixels")
+    };
+    say!("Verifying that {} {} were painted...", expected, what);
+    if found == expected {
+        say!("Found exactly {} {}!", expected, what);
+        Ok(())
+    } else {
+        Err(format!(
+            "Expected {} {}, but found {} ({:+}); a deterministic simulation should paint the same amount every run.",
+            expected,
+            what,
+            found,
+            found as i64 - expected as i64
+        ))
+    }
+}
+
+pub fn check_expected_artists(
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    expected: &HashSet<Artist>,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!("Verifying that the expected artists all painted...");
+    let mut missing: Vec<&Artist> = expected
+        .iter()
+        .filter(|artist| !posns_map.contains_key(artist))
+        .collect();
+    missing.sort();
+    let mut unexpected: Vec<&Artist> = posns_map
+        .keys()
+        .filter(|artist| !expected.contains(artist))
+        .collect();
+    unexpected.sort();
+
+    let mut offenders = OffenderList::new(top_n);
+    for artist in &missing {
+        if offenders.admit() {
+            eprintln!("Artist {} was expected, but never painted.", name(**artist));
+        }
+    }
+    offenders.finish();
+    let mut offenders = OffenderList::new(top_n);
+    for artist in &unexpected {
+        if offenders.admit() {
+            eprintln!("Artist {} painted, but was not expected.", name(**artist));
+        }
+    }
+    offenders.finish();
+
+    if missing.is_empty() && unexpected.is_empty() {
+        say!("All {} expected artists painted!", expected.len());
+        Ok(())
+    } else {
+        Err(format!(
+            "{} expected artists never painted and {} unexpected artists did; make sure every thread you spawn gets to paint, and that artists log their own ids.",
+            missing.len(),
+            unexpected.len()
+        ))
+    }
+}
+
+/// Checks that the artist ids are exactly `base`, `base + 1`, and so on with
+/// no gaps, listing the missing ids and any below `base`.
+pub fn check_ids_contiguous(
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    base: Artist,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!(
+        "Verifying that artist ids count up from {} without gaps...",
+        base
+    );
+    let highest = match posns_map.keys().max() {
+        Some(highest) => *highest,
+        None => return Err("No artists painted, so there are no ids to check.".to_string()),
+    };
+    let mut ids: Vec<Artist> = posns_map.keys().copied().collect();
+    ids.sort();
+    let split = ids.partition_point(|id| *id < base);
+    let (below, present) = ids.split_at(split);
+
+    let mut below_list = OffenderList::new(top_n);
+    for id in below {
+        if below_list.admit() {
+            eprintln!("Artist id {} is below the base id {}", name(*id), base);
+        }
+    }
+    below_list.finish();
+
+    // real thread ids are far apart, so the gaps are counted rather than walked
+    let missing = if highest < base {
+        0
+    } else {
+        ((highest - base) as u128 + 1 - present.len() as u128) as usize
+    };
+    // missing ids belong to no artist, so --anonymize prints them unnamed
+    let mut offenders = OffenderList::new(top_n);
+    let mut next = base;
+    for id in present {
+        if *id > next {
+            let gap = (*id - next) as usize;
+            if !offenders.admit_run(gap) {
+                break;
+            }
+            if gap == 1 {
+                eprintln!("Artist id {} is missing", name(next));
+            } else {
+                eprintln!("Artist ids {} to {} are missing", name(next), name(*id - 1));
+            }
+        }
+        // nothing can be missing past the largest id there is
+        match id.checked_add(1) {
+            Some(after) => next = after,
+            None => break,
+        }
+    }
+    // whatever the walk stopped short of is past the limit
+    if missing > offenders.count() {
+        offenders.admit_run(missing - offenders.count());
+    }
+    offenders.finish();
+
+    if missing > 0 || !below.is_empty() {
+        Err(format!(
+            "Artist ids should run from {} to {} without gaps, but {} are missing and {} are below {}; number artists by the order they are created.",
+            base,
+            name(highest),
+            missing,
+            below.len(),
+            base
+        ))
+    } else {
+        say!(
+            "Artist ids run from {} to {} without gaps!",
+            base,
+            name(highest)
+        );
+        Ok(())
+    }
+}
+
+/// Checks that every artist id looks like a real thread id: not 0, and
+/// between `lo` and `hi` inclusive. Ids that aren't usually come from logging
+/// a tid before it was set, or from printing the wrong variable.
+pub fn check_tids_plausible(
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    lo: Artist,
+    hi: Artist,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!(
+        "Verifying that artist ids are plausible thread ids, from {} to {}...",
+        lo,
+        hi
+    );
+    let mut implausible: Vec<&Artist> = posns_map
+        .keys()
+        .filter(|id| **id == 0 || **id < lo || **id > hi)
+        .collect();
+    implausible.sort();
+
+    let mut offenders = OffenderList::new(top_n);
+    for id in &implausible {
+        if offenders.admit() {
+            if **id == 0 {
+                eprintln!("Artist {}'s id is 0, which is not a thread id", name(**id));
+            } else {
+                eprintln!("Artist {}'s id is outside {} to {}", name(**id), lo, hi);
+            }
+        }
+    }
+    offenders.finish();
+
+    if implausible.is_empty() {
+        say!("All artist ids are plausible thread ids!");
+        Ok(())
+    } else {
+        Err(format!(
+            "Found {} artist ids that don't look like thread ids; make sure each artist logs its tid after it has been set.",
+            implausible.len()
+        ))
+    }
+}
+
+pub fn check_all_artists_draw(
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    num_pixels: usize,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!(
+        "Verifying that all artists draw at least {} pixels...",
+        num_pixels
+    );
+    let mut draw_error = false;
+    let mut artists: Vec<(&Artist, &HashSet<Point>)> = posns_map.iter().collect();
+    if deterministic() {
+        artists.sort_by_key(|(artist, _)| **artist);
+    }
+    let mut offenders = OffenderList::new(top_n);
+    for (artist, points) in artists {
+        if points.len() < num_pixels {
+            draw_error = true;
+            if offenders.admit() {
+                eprintln!(
+                    "Artist {} drew {} pixels; should draw at least {} pixels.",
+                    name(*artist),
+                    points.len(),
+                    num_pixels
+                );
+            }
+        }
+    }
+    offenders.finish();
+    if draw_error {
+        Err("Artists did not draw enough pixels: could be starved, but make sure that all artists have a chance to draw!".to_string())
+    } else {
+        say!("All artists draw at least {} pixels!", num_pixels);
+        Ok(())
+    }
+}
+
+pub fn check_max_pixels_per_artist(
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    max_pixels: usize,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!(
+        "Verifying that no artist draws more than {} pixels...",
+        max_pixels
+    );
+    let mut artists: Vec<&Artist> = posns_map.keys().collect();
+    artists.sort();
+    let mut offenders = OffenderList::new(top_n);
+    for artist in artists {
+        let drawn = posns_map[artist].len();
+        if drawn > max_pixels && offenders.admit() {
+            eprintln!(
+                "Artist {} drew {} pixels, {} over the cap of {}.",
+                name(*artist),
+                drawn,
+                drawn - max_pixels,
+                max_pixels
+            );
+        }
+    }
+    offenders.finish();
+    if offenders.count() > 0 {
+        Err(format!(
+            "Found {} artists drawing more than {} pixels: a thread may be running away, so make sure every artist stops once it reaches its limit!",
+            offenders.count(),
+            max_pixels
+        ))
+    } else {
+        say!("No artist draws more than {} pixels!", max_pixels);
+        Ok(())
+    }
+}
+
+/// Fails unless the log has exactly as many distinct colors as artists, the
+/// top-level invariant of a one-color-per-artist assignment.
+pub fn check_color_count_matches_artists(
+    canvas: &Canvas,
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+) -> Result<(), String> {
+    say!("Verifying that there are as many distinct colors as artists...");
+    let colors = canvas
+        .iter()
+        .map(|p| p.color)
+        .collect::<HashSet<Color>>()
+        .len();
+    let artists = posns_map.len();
+    if colors == artists {
+        say!("Found one color per artist!");
+        Ok(())
+    } else if colors < artists {
+        Err(format!(
+            "Found {} distinct colors for {} artists, {} fewer; some artists share a color, so check how colors are picked for collisions.",
+            colors,
+            artists,
+            artists - colors
+        ))
+    } else {
+        Err(format!(
+            "Found {} distinct colors for {} artists, {} more; some artists paint with more than one color, so make sure each artist picks its color once.",
+            colors,
+            artists,
+            colors - artists
+        ))
+    }
+}
+
+pub fn check_colors_unique(canvas: &Canvas, top_n: Option<usize>) -> Result<(), String> {
+    say!("Verifying that all artists use unique colors...");
+    let mut color_error = false;
+    let mut color_set: HashMap<Color, Artist> = HashMap::new();
+    let mut offenders = OffenderList::new(top_n);
+    for pixel in canvas {
+        if let Some(artist) = color_set.get(&pixel.color) {
+            if *artist == pixel.artist {
+                continue;
+            } else {
+                color_error = true;
+                if offenders.admit() {
+                    eprintln!(
+                        "Artist {} uses color {}, which is also used by artist {}",
+                        name(pixel.artist),
+                        pixel.color,
+                        name(*color_set.get(&pixel.color).unwrap())
+                    );
+                }
+            }
+        } else {
+            color_set.insert(pixel.color, pixel.artist);
+        }
+    }
+    offenders.finish();
+    if color_error {
+        return Err(
+            "Ensure that each artist must have a unique color, when you generate artists!"
+                .to_string(),
+        );
+    } else {
+        say!("All artists use unique colors!");
+    }
+
+    Ok(())
+}
+
+/// Fails on any artist whose first color is a gray (`r == g == b`), which in
+/// an assignment with colorful output usually means the color was never
+/// randomized, or every channel came from the same random number.
+pub fn check_no_grayscale_colors(
+    colors_map: &HashMap<Artist, Color>,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!("Verifying that no artist paints in grayscale...");
+    let mut artists: Vec<&Artist> = colors_map.keys().collect();
+    artists.sort();
+    let mut offenders = OffenderList::new(top_n);
+    for artist in artists {
+        let color = colors_map[artist];
+        if color.is_grayscale() && offenders.admit() {
+            eprintln!("Artist {} paints in grayscale: {}", name(*artist), color);
+        }
+    }
+    offenders.finish();
+    if offenders.count() > 0 {
+        Err(format!(
+            "Found {} artists painting in grayscale; draw each color channel from its own random number, and make sure the color is set before painting.",
+            offenders.count()
+        ))
+    } else {
+        say!("Every artist paints in color!");
+        Ok(())
+    }
+}
+
+/// Colors closer than this in RGB are considered indistinguishable.
+pub const CVD_DISTANCE_THRESHOLD: f64 = 20.0;
+
+/// Colors closer than this Delta E in CIELAB are considered indistinguishable.
+pub const CVD_DELTA_E_THRESHOLD: f64 = 10.0;
+
+pub fn check_colors_distinguishable_cvd(
+    colors_map: &HashMap<Artist, Color>,
+    metric: ColorMetric,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    let threshold = match metric {
+        ColorMetric::Rgb => CVD_DISTANCE_THRESHOLD,
+        ColorMetric::Cielab => CVD_DELTA_E_THRESHOLD,
+    };
+    say!("Verifying that artist colors are distinguishable under color-vision deficiencies...");
+    let mut artists: Vec<(&Artist, &Color)> = colors_map.iter().collect();
+    artists.sort_by_key(|(artist, _)| **artist);
+    let mut pairs = 0;
+    let mut offenders = OffenderList::new(top_n);
+    for deficiency in [Deficiency::Protanopia, Deficiency::Deuteranopia].iter() {
+        for (i, (artist, color)) in artists.iter().enumerate() {
+            for (other_artist, other_color) in artists.iter().skip(i + 1) {
+                // only pairs that were distinguishable to begin with; identical
+                // colors are the unique color check's business
+                if metric.distance(color, other_color) < threshold {
+                    continue;
+                }
+                let simulated = color.simulate(*deficiency);
+                let other_simulated = other_color.simulate(*deficiency);
+                if metric.distance(&simulated, &other_simulated) < threshold {
+                    pairs += 1;
+                    if offenders.admit() {
+                        eprintln!(
+                            "Artists {} and {} use colors {} and {}, which look like {} and {} with {}",
+                            name(**artist), name(**other_artist), color, other_color, simulated, other_simulated, deficiency
+                        );
+                    }
+                }
+            }
+        }
+    }
+    offenders.finish();
+    if pairs > 0 {
+        Err(format!(
+            "Found {} pairs of artist colors that are indistinguishable with a color-vision deficiency; pick colors that differ in brightness, not just hue.",
+            pairs
+        ))
+    } else {
+        say!("All artist colors are distinguishable under color-vision deficiencies!");
+        Ok(())
+    }
+}
+
+/// Recomputes each artist's color from its id and fails on any artist whose
+/// first color doesn't match.
+pub fn check_colors_derived(
+    colors_map: &HashMap<Artist, Color>,
+    derivation: ColorDerivation,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!(
+        "Verifying that artist colors are derived from their ids with {}...",
+        derivation
+    );
+    let mut artists: Vec<&Artist> = colors_map.keys().collect();
+    artists.sort();
+    let mut offenders = OffenderList::new(top_n);
+    for artist in artists {
+        let expected = derivation.color_for(*artist);
+        if colors_map[artist] != expected && offenders.admit() {
+            eprintln!(
+                "Artist {} painted with {}, but {} gives {}",
+                name(*artist),
+                colors_map[artist],
+                derivation,
+                expected
+            );
+        }
+    }
+    offenders.finish();
+    if offenders.count() > 0 {
+        Err(format!(
+            "Found {} artists whose color doesn't match the {} derivation; check that the color is computed from the thread's own id.",
+            offenders.count(),
+            derivation
+        ))
+    } else {
+        say!("Every artist's color matches its id!");
+        Ok(())
+    }
+}
+
+/// Fails on the first pixel whose timestamp is earlier than the one logged before it.
+pub fn check_timestamps_ordered(canvas: &Canvas) -> Result<(), String> {
+    say!("Verifying that timestamps never decrease...");
+    let timestamped = canvas
+        .iter()
+        .enumerate()
+        .filter_map(|(i, p)| p.timestamp.map(|t| (i, p, t)));
+    let mut previous: Option<(&CanvasPixel, u64)> = None;
+    for (i, pixel, timestamp) in timestamped {
+        if let Some((prev_pixel, prev_timestamp)) = previous {
+            if timestamp < prev_timestamp {
+                return Err(format!(
+                    "Pixel {} (artist {}) has timestamp {}, earlier than the {} logged before it by artist {}; lines from different threads are being written out of order; take the timestamp and write the line while holding the same lock.",
+                    i + 1,
+                    name(pixel.artist),
+                    timestamp,
+                    prev_timestamp,
+                    name(prev_pixel.artist)
+                ));
+            }
+        }
+        previous = Some((pixel, timestamp));
+    }
+    say!("All timestamps are in order!");
+    Ok(())
+}
+
+// fraction of an artist's painting time two paints of the same point must be
+// apart before they look like two different truncated coordinates
+const ALIAS_TIME_FRACTION: f64 = 0.5;
+
+/// Looks for coordinates an artist painted twice in the same color, far apart
+/// in time, as happens when larger coordinates are truncated into `i16` and
+/// distinct positions land on the same point. Only timestamped pixels count.
+pub fn check_no_aliased_points(canvas: &Canvas, top_n: Option<usize>) -> Result<(), String> {
+    say!("Checking for points that look aliased by coordinate truncation...");
+    // each artist's first and last timestamp, and each (artist, point, color)'s
+    let mut active: HashMap<Artist, (u64, u64)> = HashMap::new();
+    let mut paints: HashMap<(Artist, Point, Color), (u64, u64, usize)> = HashMap::new();
+    for pixel in canvas {
+        let timestamp = match pixel.timestamp {
+            Some(timestamp) => timestamp,
+            None => continue,
+        };
+        let span = active.entry(pixel.artist).or_insert((timestamp, timestamp));
+        *span = (span.0.min(timestamp), span.1.max(timestamp));
+        let seen = paints
+            .entry((pixel.artist, pixel.coord, pixel.color))
+            .or_insert((timestamp, timestamp, 0));
+        *seen = (seen.0.min(timestamp), seen.1.max(timestamp), seen.2 + 1);
+    }
+
+    let mut suspects: Vec<(Artist, Point, u64, usize)> = paints
+        .into_iter()
+        .filter(|((artist, _, _), (first, last, count))| {
+            let (start, end) = active[artist];
+            *count > 1 && (last - first) as f64 > (end - start) as f64 * ALIAS_TIME_FRACTION
+        })
+        .map(|((artist, point, _), (first, last, count))| (artist, point, last - first, count))
+        .collect();
+    suspects.sort_by_key(|(artist, point, _, _)| (*artist, point.x, point.y));
+
+    let mut offenders = OffenderList::new(top_n);
+    for (artist, point, spread, count) in &suspects {
+        if offenders.admit() {
+            eprintln!(
+                "Artist {} painted ({}, {}) {} times in the same color, {} apart",
+                name(*artist),
+                point.x,
+                point.y,
+                count,
+                spread
+            );
+        }
+    }
+    offenders.finish();
+    if suspects.is_empty() {
+        say!("No aliased points found!");
+        Ok(())
+    } else {
+        Err(format!(
+            "Found {} points repainted by the same artist long after the first paint; coordinates may be truncated into 16 bits, so check the canvas size fits in an i16.",
+            suspects.len()
+        ))
+    }
+}
+
+// how many times an artist's median time between pixels a single gap must
+// be before it looks like lines went missing
+const GAP_FACTOR: u64 = 10;
+
+/// Looks for long pauses in each artist's timestamps relative to how often
+/// that artist usually paints, as left behind by dropped log lines. Artists
+/// with fewer than three timestamped pixels have no cadence to compare to.
+pub fn check_no_timestamp_gaps(canvas: &Canvas, top_n: Option<usize>) -> Result<(), String> {
+    say!("Checking for gaps in artists' timestamps that suggest dropped lines...");
+    let mut times: HashMap<Artist, Vec<u64>> = HashMap::new();
+    for pixel in canvas {
+        if let Some(timestamp) = pixel.timestamp {
+            times.entry(pixel.artist).or_default().push(timestamp);
+        }
+    }
+    let mut artists: Vec<&Artist> = times.keys().collect();
+    artists.sort();
+
+    let mut gaps = 0;
+    let mut offenders = OffenderList::new(top_n);
+    for artist in artists {
+        let mut timestamps = times[artist].clone();
+        if timestamps.len() < 3 {
+            continue;
+        }
+        timestamps.sort_unstable();
+        let deltas: Vec<u64> = timestamps.windows(2).map(|w| w[1] - w[0]).collect();
+        let mut sorted = deltas.clone();
+        sorted.sort_unstable();
+        let median = sorted[sorted.len() / 2].max(1);
+        for (i, delta) in deltas.iter().enumerate() {
+            if *delta > median * GAP_FACTOR {
+                gaps += 1;
+                if offenders.admit() {
+                    eprintln!(
+                        "Artist {} went {} without painting, from {} to {}; it usually paints every {}",
+                        name(*artist),
+                        delta,
+                        timestamps[i],
+                        timestamps[i + 1],
+                        median
+                    );
+                }
+            }
+        }
+    }
+    offenders.finish();
+    if gaps > 0 {
+        Err(format!(
+            "Found {} gaps over {} times an artist's usual cadence; some log lines may have been dropped, so make sure every paint is logged and the log is flushed before exit.",
+            gaps, GAP_FACTOR
+        ))
+    } else {
+        say!("No suspicious gaps in artist timestamps!");
+        Ok(())
+    }
+}
+
+pub fn check_no_protected_pixels(
+    canvas: &Canvas,
+    protected: &HashSet<Point>,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!("Verifying that no artists paint on the protected background...");
+    let mut offenders = OffenderList::new(top_n);
+    for pixel in canvas {
+        if protected.contains(&pixel.coord) && offenders.admit() {
+            eprintln!(
+                "Artist {} painted protected position ({}, {})",
+                name(pixel.artist),
+                pixel.coord.x,
+                pixel.coord.y
+            );
+        }
+    }
+    offenders.finish();
+    if offenders.count() > 0 {
+        Err(format!(
+            "Found {} pixels painted on the protected background; artists must skip positions outside their drawable area.",
+            offenders.count()
+        ))
+    } else {
+        say!("No artists paint on the protected background!");
+        Ok(())
+    }
+}
+
+/// Fails on any pixel painted in a color outside `palette`, listing each
+/// artist's off-palette colors. Palette colors nobody used are reported too,
+/// but don't fail the check.
+pub fn check_colors_in_palette(
+    canvas: &Canvas,
+    palette: &HashSet<Color>,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!(
+        "Verifying that artists only paint with the {} palette colors...",
+        palette.len()
+    );
+    let mut off_palette: HashMap<Artist, Vec<Color>> = HashMap::new();
+    let mut used: HashSet<Color> = HashSet::new();
+    let mut pixels = 0;
+    for pixel in canvas {
+        if palette.contains(&pixel.color) {
+            used.insert(pixel.color);
+        } else {
+            pixels += 1;
+            let colors = off_palette.entry(pixel.artist).or_default();
+            if !colors.contains(&pixel.color) {
+                colors.push(pixel.color);
+            }
+        }
+    }
+    let mut artists: Vec<&Artist> = off_palette.keys().collect();
+    artists.sort();
+    let mut offenders = OffenderList::new(top_n);
+    for artist in artists {
+        if offenders.admit() {
+            let colors: Vec<String> = off_palette[artist].iter().map(Color::to_string).collect();
+            eprintln!(
+                "Artist {} painted with colors outside the palette: {}",
+                name(*artist),
+                colors.join(", ")
+            );
+        }
+    }
+    offenders.finish();
+
+    let mut unused: Vec<&Color> = palette.difference(&used).collect();
+    unused.sort_by_key(|color| (color.r, color.g, color.b));
+    if unused.is_empty() {
+        say!("Every palette color was used.");
+    } else {
+        let unused: Vec<String> = unused.iter().map(|color| color.to_string()).collect();
+        say!(
+            "{} of {} palette colors were never used: {}",
+            unused.len(),
+            palette.len(),
+            unused.join(", ")
+        );
+    }
+
+    if offenders.count() > 0 {
+        Err(format!(
+            "Found {} pixels from {} artists painted outside the palette; artists must pick their colors from the palette.",
+            pixels,
+            offenders.count()
+        ))
+    } else {
+        say!("All pixels use palette colors!");
+        Ok(())
+    }
+}
+
+/// What becomes of an artist painting a position it already painted.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum SelfOverlap {
+    /// Say nothing.
+    Ignore,
+    /// List each repaint while reading the log. The default.
+    #[default]
+    Warn,
+    /// Fail the `self-overlap` check on any repaint.
+    Error,
+}
+
+impl FromStr for SelfOverlap {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ignore" => Ok(SelfOverlap::Ignore),
+            "warn" => Ok(SelfOverlap::Warn),
+            "error" => Ok(SelfOverlap::Error),
+            _ => Err(format!(
+                "Unknown self-overlap policy {}; expected ignore, warn, or error",
+                s
+            )),
+        }
+    }
+}
+
+/// Fails on any artist painting a position it already painted, in the order
+/// the repaints were logged.
+pub fn check_no_self_overlap(canvas: &Canvas, top_n: Option<usize>) -> Result<(), String> {
+    say!("Verifying that no artist paints over its own pixels...");
+    let mut painted: HashSet<(Artist, Point)> = HashSet::new();
+    let mut offenders = OffenderList::new(top_n);
+    for pixel in canvas {
+        if !painted.insert((pixel.artist, pixel.coord)) && offenders.admit() {
+            eprintln!(
+                "Artist {} already painted at position {:#?}!",
+                name(pixel.artist),
+                pixel.coord
+            );
+        }
+    }
+    offenders.finish();
+    if offenders.count() > 0 {
+        Err(format!(
+            "Found {} repaints of an artist's own pixels; have each artist remember the positions it has painted and move on from them.",
+            offenders.count()
+        ))
+    } else {
+        say!("No artist painted over itself!");
+        Ok(())
+    }
+}
+
+/// Lists every pair of artists painting the same points, with the color each
+/// left there, failing once more than `max_overlaps` distinct points are
+/// painted by more than one artist.
+pub fn check_no_overlapping(
+    canvas: &Canvas,
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    max_overlaps: usize,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!("Verifying that no artists paint over one another...");
+    // the last color each artist painted on each of its points
+    let mut colors: HashMap<(Artist, Point), Color> = HashMap::new();
+    for pixel in canvas {
+        colors.insert((pixel.artist, pixel.coord), pixel.color);
+    }
+    let mut artists: Vec<(&Artist, &HashSet<Point>)> = posns_map.iter().collect();
+    if deterministic() {
+        artists.sort_by_key(|(artist, _)| **artist);
+    }
+    let mut overlapping: HashSet<Point> = HashSet::new();
+    let mut offenders = OffenderList::new(top_n);
+    for (artist, posns) in &artists {
+        for (other_artist, other_posns) in &artists {
+            if artist == other_artist {
+                continue;
+            } else {
+                let mut intersect: Vec<&Point> = posns.intersection(other_posns).collect();
+                if deterministic() {
+                    intersect.sort_by_key(|p| (p.x, p.y));
+                }
+                if !intersect.is_empty() {
+                    overlapping.extend(intersect.iter().copied());
+                    if !offenders.admit() {
+                        continue;
+                    }
+                    eprintln!(
+                        "Artist {} overlaps with artist {} at the following points:",
+                        name(**artist),
+                        name(**other_artist)
+                    );
+                    let mut points = OffenderList::nested(top_n);
+                    for point in intersect {
+                        if points.admit() {
+                            eprintln!("{:#?}", point);
+                            let color = colors[&(**artist, *point)];
+                            let other_color = colors[&(**other_artist, *point)];
+                            eprintln!(
+                                "  in {} by artist {} and {} by artist {}{}",
+                                color,
+                                name(**artist),
+                                other_color,
+                                name(**other_artist),
+                                if color == other_color {
+                                    " (same color)"
+                                } else {
+                                    ""
+                                }
+                            );
+                        }
+                    }
+                    points.finish();
+                    eprintln!("All errors for artist {} complete.", name(**artist));
+                }
+            }
+        }
+    }
+    offenders.finish();
+    if overlapping.len() > max_overlaps {
+        return Err(format!(
+            "Found {} overlapping positions, more than the {} allowed. Make sure that artists do not paint to the same position- you may need to lock the position or ensure artists skip the position if it is locked.",
+            overlapping.len(),
+            max_overlaps
+        ));
+    } else if !overlapping.is_empty() {
+        warn!(
+            "found {} overlapping positions, within the {} allowed.",
+            overlapping.len(),
+            max_overlaps
+        );
+    } else {
+        say!("All artists paint on separate pixels!");
+    }
+
+    Ok(())
+}
+
+/// Fails on pairs of artists that painted exactly the same set of absolute
+/// coordinates. Partial overlaps are left to `check_no_overlapping`, and the
+/// same pattern in different places to `check_no_repeating_patterns`.
+pub fn check_no_coincident_artists(
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!("Verifying that no two artists painted exactly the same pixels...");
+    // artists grouped by their sorted coordinates
+    let mut regions: HashMap<Vec<(i16, i16)>, Vec<Artist>> = HashMap::new();
+    for (artist, points) in posns_map {
+        let mut coords: Vec<(i16, i16)> = points.iter().map(|p| (p.x, p.y)).collect();
+        coords.sort_unstable();
+        regions.entry(coords).or_default().push(*artist);
+    }
+    let mut pairs: Vec<(Artist, Artist)> = Vec::new();
+    for artists in regions.values_mut() {
+        artists.sort_unstable();
+        for (i, artist) in artists.iter().enumerate() {
+            for other in &artists[i + 1..] {
+                pairs.push((*artist, *other));
+            }
+        }
+    }
+    pairs.sort_unstable();
+
+    let mut offenders = OffenderList::new(top_n);
+    for (artist, other) in &pairs {
+        if offenders.admit() {
+            eprintln!(
+                "Artists {} and {} painted exactly the same {} pixels",
+                name(*artist),
+                name(*other),
+                posns_map[artist].len()
+            );
+        }
+    }
+    offenders.finish();
+    if pairs.is_empty() {
+        say!("No two artists painted the same region!");
+        Ok(())
+    } else {
+        Err(format!(
+            "Found {} pairs of artists that painted exactly the same pixels; they are likely one thread's work logged twice, or threads given the same region to paint.",
+            pairs.len()
+        ))
+    }
+}
+
+/// Fails on colors painted in more than one separate region, for logs where
+/// each color should be one artist's single connected blob: a color in two
+/// places means two artists picked the same color, or one artist's region
+/// broke apart.
+pub fn check_colors_connected(canvas: &Canvas, top_n: Option<usize>) -> Result<(), String> {
+    say!("Verifying that each color covers one connected region...");
+    let mut regions: HashMap<Color, HashSet<Point>> = HashMap::new();
+    for pixel in canvas {
+        regions.entry(pixel.color).or_default().insert(pixel.coord);
+    }
+    let mut colors: Vec<&Color> = regions.keys().collect();
+    colors.sort_by_key(|c| (c.r, c.g, c.b));
+
+    let mut offenders = OffenderList::new(top_n);
+    for color in colors {
+        let pieces = pieces(&regions[color]);
+        if pieces.len() > 1 && offenders.admit() {
+            let samples: Vec<String> = pieces
+                .iter()
+                .map(|(size, p)| format!("({}, {}) with {} pixels", p.x, p.y, size))
+                .collect();
+            eprintln!(
+                "Color {} is in {} separate regions: {}",
+                color,
+                pieces.len(),
+                samples.join(", ")
+            );
+        }
+    }
+    offenders.finish();
+    if offenders.count() > 0 {
+        Err(format!(
+            "Found {} colors split across separate regions; either artists are colliding on colors, or an artist's region is breaking apart.",
+            offenders.count()
+        ))
+    } else {
+        say!("Every color covers one connected region!");
+        Ok(())
+    }
+}
+
+/// Fails on stray pixels: ones more than `max_distance` outside the convex
+/// hull of the main drawing, the biggest group of painted points no more
+/// than `max_distance` apart, as when an artist paints one pixel at a wild
+/// coordinate.
+pub fn check_no_outliers(
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    max_distance: f64,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!(
+        "Verifying that no pixels stray more than {} from the main drawing...",
+        max_distance
+    );
+    let painted: HashSet<Point> = posns_map.values().flatten().copied().collect();
+    let hull = convex_hull(&largest_cluster(&painted, max_distance));
+
+    let mut outliers: Vec<(Artist, Point, f64)> = Vec::new();
+    for (artist, points) in posns_map {
+        for point in points {
+            let distance = hull_distance(&hull, *point);
+            if distance > max_distance {
+                outliers.push((*artist, *point, distance));
+            }
+        }
+    }
+    outliers.sort_by_key(|(artist, point, _)| (*artist, point.x, point.y));
+
+    let mut offenders = OffenderList::new(top_n);
+    for (artist, point, distance) in &outliers {
+        if offenders.admit() {
+            eprintln!(
+                "Artist {} painted ({}, {}), {:.1} outside the main drawing",
+                name(*artist),
+                point.x,
+                point.y,
+                distance
+            );
+        }
+    }
+    offenders.finish();
+
+    if outliers.is_empty() {
+        say!("No stray pixels found!");
+        Ok(())
+    } else {
+        Err(format!(
+            "Found {} pixels far outside the main drawing; check how those artists work out their coordinates, for an unset or overflowing position.",
+            outliers.len()
+        ))
+    }
+}
+
+pub fn check_no_holes(
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!("Verifying that no artist's region has holes in it...");
+    let mut artists: Vec<&Artist> = posns_map.keys().collect();
+    artists.sort();
+    let mut offenders = OffenderList::new(top_n);
+    for artist in artists {
+        let sizes = holes(&posns_map[artist]);
+        if !sizes.is_empty() && offenders.admit() {
+            let sizes: Vec<String> = sizes.iter().map(|s| s.to_string()).collect();
+            eprintln!(
+                "Artist {} has {} holes, of sizes {}",
+                name(*artist),
+                sizes.len(),
+                sizes.join(", ")
+            );
+        }
+    }
+    offenders.finish();
+    if offenders.count() > 0 {
+        Err(format!(
+            "Found {} artists whose regions enclose cells they did not paint; artists should fill in their shape as they grow it.",
+            offenders.count()
+        ))
+    } else {
+        say!("No artist regions have holes!");
+        Ok(())
+    }
+}
+
+pub fn check_no_repeating_patterns(
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!("Checking for duplicated artist patterns...");
+    let mut normalized: Vec<(Artist, Shape)> = posns_map
+        .iter()
+        .map(|(artist, set)| {
+            (
+                *artist,
+                normalize_points(set).expect("Failed to normalize set: "),
+            )
+        })
+        .collect();
+    if deterministic() {
+        normalized.sort_by_key(|(artist, _)| *artist);
+    }
+
+    let mut duplicates: HashSet<(Artist, Artist)> = HashSet::new();
+    let mut count = 0;
+    let mut offenders = OffenderList::new(top_n);
+    for (artist, set) in normalized.iter() {
+        for (other_artist, other_set) in normalized.iter() {
+            if artist == other_artist {
+                continue;
+            } else if set.is_subset(other_set) && other_set.is_superset(set) {
+                count += 1;
+                let is_new =
+                    duplicates.insert((min(*artist, *other_artist), max(*artist, *other_artist)));
+                if is_new && offenders.admit() {
+                    eprintln!("Duplicate pattern found with artists {} and {}! So far, found {} duplicates", name(*artist), name(*other_artist), duplicates.len());
+                }
+            }
+        }
+    }
+
+    offenders.finish();
+
+    if duplicates.is_empty() {
+        say!("No duplicate patterns found!");
+        Ok(())
+    } else {
+        Err(format!(
+            "Found {} duplicate patterns: {} adds",
+            duplicates.len(),
+            count
+        ))
+    }
+}
+
+// share of the pixels two same-colored artists must have in common before
+// they look like a single thread logged under two ids
+const DOUBLE_LOGGED_SIMILARITY: f64 = 0.9;
+
+/// Looks for pairs of artists with the same color whose pixels are nearly the
+/// same set, or split one region between them, as happens when one thread is
+/// logged under two ids.
+pub fn check_no_double_logged_artists(
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    colors_map: &HashMap<Artist, Color>,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!("Checking for threads logged under two artist ids...");
+    let mut artists: Vec<&Artist> = posns_map.keys().collect();
+    artists.sort();
+    let mut offenders = OffenderList::new(top_n);
+    for (i, artist) in artists.iter().enumerate() {
+        for other in &artists[i + 1..] {
+            if colors_map.get(artist) != colors_map.get(other) {
+                continue;
+            }
+            let (points, other_points) = (&posns_map[artist], &posns_map[other]);
+            let shared = points.intersection(other_points).count();
+            let similarity = shared as f64 / points.union(other_points).count() as f64;
+            let evidence = if similarity >= DOUBLE_LOGGED_SIMILARITY {
+                format!(
+                    "share {} of their pixels ({:.0}% the same)",
+                    shared,
+                    100.0 * similarity
+                )
+            } else {
+                let edges: usize = points
+                    .iter()
+                    .map(|p| {
+                        p.neighbors()
+                            .iter()
+                            .filter(|n| other_points.contains(n))
+                            .count()
+                    })
+                    .sum();
+                if shared > 0 || edges == 0 {
+                    continue;
+                }
+                format!(
+                    "never overlap but border each other along {} edges, splitting one region",
+                    edges
+                )
+            };
+            if offenders.admit() {
+                eprintln!(
+                    "Artists {} and {} both paint with {} and {}",
+                    name(**artist),
+                    name(**other),
+                    colors_map[*artist],
+                    evidence
+                );
+            }
+        }
+    }
+    offenders.finish();
+    if offenders.count() > 0 {
+        Err(format!(
+            "Found {} pairs of artist ids that look like the same thread logged twice; make sure each thread logs only under its own id, once.",
+            offenders.count()
+        ))
+    } else {
+        say!("No threads look logged twice!");
+        Ok(())
+    }
+}
+
+/// Looks for pairs of artists where one drew the left-right or top-bottom
+/// mirror image of the other's pattern. Identical patterns are left to
+/// `check_no_repeating_patterns`.
+pub fn check_no_mirrored_patterns(
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!("Checking for artists that mirrored each other's patterns...");
+    let mut artists: Vec<&Artist> = posns_map.keys().collect();
+    artists.sort();
+    let shapes: Vec<(Artist, Shape)> = artists
+        .iter()
+        .map(|artist| (**artist, shape(&posns_map[artist], Transform::Identity)))
+        .collect();
+
+    let mut offenders = OffenderList::new(top_n);
+    for (i, (artist, original)) in shapes.iter().enumerate() {
+        let mirrors = [Transform::FlipHorizontal, Transform::FlipVertical]
+            .map(|axis| (axis, shape(&posns_map[artist], axis)));
+        for (other_artist, other) in &shapes[i + 1..] {
+            if original == other {
+                continue;
+            }
+            // a pattern can match both mirrors, as a 180 degree turn of
+            // itself; the pair is still one offender, named by the first
+            let axis = mirrors
+                .iter()
+                .find(|(_, mirrored)| mirrored == other)
+                .map(|(axis, _)| axis);
+            if let Some(axis) = axis {
+                if offenders.admit() {
+                    eprintln!(
+                        "Artist {}'s pattern is artist {}'s {}!",
+                        name(*other_artist),
+                        name(*artist),
+                        axis
+                    );
+                }
+            }
+        }
+    }
+    offenders.finish();
+
+    if offenders.count() > 0 {
+        Err(format!(
+            "Found {} mirror-image duplicate patterns; artists mirroring each other usually share copy-pasted drawing code.",
+            offenders.count()
+        ))
+    } else {
+        say!("No mirrored patterns found!");
+        Ok(())
+    }
+}
+
+/// How many matching moves in a row `check_no_shared_rng` takes as a sign of
+/// a shared random number generator, unless told otherwise.
+pub const SHARED_RNG_RUN: usize = 16;
+
+/// Fails on pairs of artists whose moves, the steps between the pixels they
+/// painted one after the other, match for at least `min_run` steps in a row.
+/// Artists drawing from one random stream take the same steps even when they
+/// start in different places. A run of one step repeated, as when scanning a
+/// row, isn't taken as evidence.
+pub fn check_no_shared_rng(
+    canvas: &Canvas,
+    min_run: usize,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!(
+        "Verifying that no two artists take the same {} moves in a row...",
+        min_run
+    );
+    let mut painted: HashMap<Artist, Vec<&CanvasPixel>> = HashMap::new();
+    for pixel in canvas {
+        painted.entry(pixel.artist).or_default().push(pixel);
+    }
+    let moves: HashMap<Artist, Vec<(i32, i32)>> = painted
+        .into_iter()
+        .map(|(artist, mut pixels)| {
+            pixels.sort_by_key(|pixel| pixel.seq);
+            let steps = pixels
+                .windows(2)
+                .map(|pair| {
+                    (
+                        pair[1].coord.x as i32 - pair[0].coord.x as i32,
+                        pair[1].coord.y as i32 - pair[0].coord.y as i32,
+                    )
+                })
+                .collect();
+            (artist, steps)
+        })
+        .collect();
+
+    // pairs sharing any `min_run` moves in a row, found through the runs
+    // each artist takes
+    let mut runs: HashMap<&[(i32, i32)], Vec<Artist>> = HashMap::new();
+    for (artist, steps) in &moves {
+        for run in steps.windows(min_run.max(1)) {
+            if run.iter().all(|step| *step == run[0]) {
+                continue;
+            }
+            let takers = runs.entry(run).or_default();
+            if !takers.contains(artist) {
+                takers.push(*artist);
+            }
+        }
+    }
+    let mut pairs: HashSet<(Artist, Artist)> = HashSet::new();
+    for takers in runs.values() {
+        for (i, artist) in takers.iter().enumerate() {
+            for other in &takers[i + 1..] {
+                pairs.insert((*artist.min(other), *artist.max(other)));
+            }
+        }
+    }
+    let mut pairs: Vec<(Artist, Artist)> = pairs.into_iter().collect();
+    pairs.sort();
+
+    let mut offenders = OffenderList::new(top_n);
+    for (artist, other) in pairs {
+        let longest = longest_common_run(&moves[&artist], &moves[&other]);
+        if offenders.admit() {
+            eprintln!(
+                "Artists {} and {} take the same {} moves in a row",
+                name(artist),
+                name(other),
+                longest
+            );
+        }
+    }
+    offenders.finish();
+
+    if offenders.count() > 0 {
+        Err(format!(
+            "Found {} pairs of artists moving in lockstep; give each thread its own random number generator, seeded differently, instead of sharing one.",
+            offenders.count()
+        ))
+    } else {
+        say!("No artists share a random stream!");
+        Ok(())
+    }
+}
+
+// the length of the longest stretch `a` and `b` have in common
+fn longest_common_run(a: &[(i32, i32)], b: &[(i32, i32)]) -> usize {
+    let mut longest = 0;
+    let mut previous = vec![0; b.len() + 1];
+    for step in a {
+        let mut current = vec![0; b.len() + 1];
+        for (j, other) in b.iter().enumerate() {
+            if step == other {
+                current[j + 1] = previous[j] + 1;
+                longest = max(longest, current[j + 1]);
+            }
+        }
+        previous = current;
+    }
+    longest
+}
+
+/// Warns about artists whose points sit on a regular lattice, every `dx`-th
+/// column or `dy`-th row, rather than forming a contiguous shape. This is a
+/// heuristic, so it never fails the run.
+pub fn check_lattice_patterns(
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!("Checking for artists painting on a lattice...");
+    let mut artists: Vec<&Artist> = posns_map.keys().collect();
+    artists.sort();
+    let mut offenders = OffenderList::new(top_n);
+    for artist in artists {
+        if let Some((dx, dy)) = lattice_period(&posns_map[artist]) {
+            if offenders.admit() {
+                warn!(
+                    "artist {} paints every {} columns and every {} rows",
+                    name(*artist),
+                    dx,
+                    dy
+                );
+            }
+        }
+    }
+    offenders.finish();
+    if offenders.count() > 0 {
+        warn!(
+            "found {} artists painting on a lattice; check that the step between positions is 1, not the number of threads or the pattern size.",
+            offenders.count()
+        );
+    } else {
+        say!("No lattice patterns found!");
+    }
+    Ok(())
+}
+
+/// Warns about artists whose every pixel lies on the edge of a `width` by
+/// `height` canvas, as a thread tracing the border instead of its own region
+/// would paint. This is a heuristic, so it never fails the run.
+pub fn check_border_only_artists(
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    width: usize,
+    height: usize,
+    top_n: Option<usize>,
+) -> Result<(), String> {
+    say!("Checking for artists painting only the canvas border...");
+    let on_border = |p: &Point| {
+        let (x, y) = (p.x as i64, p.y as i64);
+        x == 0 || y == 0 || x == width as i64 - 1 || y == height as i64 - 1
+    };
+    let mut artists: Vec<&Artist> = posns_map.keys().collect();
+    artists.sort();
+    let mut offenders = OffenderList::new(top_n);
+    for artist in artists {
+        let points = &posns_map[artist];
+        if !points.is_empty() && points.iter().all(on_border) && offenders.admit() {
+            warn!(
+                "artist {} painted all {} of its pixels on the border",
+                name(*artist),
+                points.len()
+            );
+        }
+    }
+    offenders.finish();
+    if offenders.count() > 0 {
+        warn!(
+            "found {} artists painting only the canvas border; check that their loops cover the inside of their region, not just its edges.",
+            offenders.count()
+        );
+    } else {
+        say!("No artists paint only the border!");
+    }
+    Ok(())
+}
+
+pub fn normalize_points(points: &HashSet<Point>) -> Result<Shape, String> {
+    // find the rightmost point for the relative "origin", ties going to the
+    // largest y. Point's own order only looks at x, which left ties to the
+    // set's iteration order and so varied between runs. the canvas origin
+    // (which flips y) flips every pattern alike, so flipped patterns are
+    // still equal exactly when the originals are. the differences are taken
+    // in i32, since points at opposite ends of the i16 range are further
+    // apart than an i16 holds
+    let rightmost = match points.iter().max_by_key(|p| (p.x, p.y)) {
+        Some(point) => point,
+        None => {
+            return Err("Error finding rightmost point in the set of points.".to_string());
+        }
+    };
+
+    Ok(points
+        .iter()
+        .map(|p| {
+            (
+                p.x as i32 - rightmost.x as i32,
+                p.y as i32 - rightmost.y as i32,
+            )
+        })
+        .collect::<Shape>())
+}
@@ -0,0 +1,278 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::canvas::Artist;
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, Serialize)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.r, self.g, self.b)
+    }
+}
+
+impl Color {
+    pub fn new(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b }
+    }
+
+    /// The color packed into one integer as `0xRRGGBB`, or None past `0xFFFFFF`.
+    ///
+    /// ```
+    /// use log_reader::Color;
+    ///
+    /// assert_eq!(Color::from_packed(0), Some(Color::new(0, 0, 0)));
+    /// assert_eq!(Color::from_packed(0x12AB34), Some(Color::new(0x12, 0xAB, 0x34)));
+    /// assert_eq!(Color::from_packed(16777215), Some(Color::new(255, 255, 255)));
+    /// assert_eq!(Color::from_packed(16777216), None);
+    /// ```
+    pub fn from_packed(packed: u32) -> Option<Color> {
+        if packed > 0xFFFFFF {
+            return None;
+        }
+        Some(Color::new(
+            (packed >> 16) as u8,
+            (packed >> 8) as u8,
+            packed as u8,
+        ))
+    }
+
+    /// Straight-line distance between the two colors in RGB space.
+    pub fn distance(&self, other: &Color) -> f64 {
+        let dr = self.r as f64 - other.r as f64;
+        let dg = self.g as f64 - other.g as f64;
+        let db = self.b as f64 - other.b as f64;
+        (dr * dr + dg * dg + db * db).sqrt()
+    }
+
+    /// Perceived brightness, from 0 (black) to 255 (white).
+    pub fn luminance(&self) -> f64 {
+        0.299 * self.r as f64 + 0.587 * self.g as f64 + 0.114 * self.b as f64
+    }
+
+    /// Whether all three channels are equal, so the color is a shade of gray
+    /// between black and white.
+    pub fn is_grayscale(&self) -> bool {
+        self.r == self.g && self.g == self.b
+    }
+
+    /// The color in CIELAB, `[L, a, b]`, through linear sRGB and CIE XYZ
+    /// under the D65 white point.
+    pub fn to_lab(&self) -> [f64; 3] {
+        let (r, g, b) = (
+            srgb_to_linear(self.r),
+            srgb_to_linear(self.g),
+            srgb_to_linear(self.b),
+        );
+        // XYZ scaled by the white point, so white is 1 on every axis
+        let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+        let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+        let f = |t: f64| {
+            if t > 216.0 / 24389.0 {
+                t.cbrt()
+            } else {
+                (24389.0 / 27.0 * t + 16.0) / 116.0
+            }
+        };
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+        [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+    }
+
+    /// Perceptual distance between the two colors, CIE76 Delta E: straight
+    /// line distance in CIELAB, where about 2.3 is just noticeable.
+    ///
+    /// ```
+    /// use log_reader::Color;
+    ///
+    /// let white = Color::new(255, 255, 255);
+    /// let black = Color::new(0, 0, 0);
+    /// assert!((white.delta_e(&black) - 100.0).abs() < 0.01);
+    /// ```
+    pub fn delta_e(&self, other: &Color) -> f64 {
+        let (a, b) = (self.to_lab(), other.to_lab());
+        ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+    }
+
+    /// How this color appears to someone with the given deficiency.
+    pub fn simulate(&self, deficiency: Deficiency) -> Color {
+        let linear = [
+            srgb_to_linear(self.r),
+            srgb_to_linear(self.g),
+            srgb_to_linear(self.b),
+        ];
+        let matrix = deficiency.matrix();
+        let mut channels = [0u8; 3];
+        for (channel, row) in channels.iter_mut().zip(matrix.iter()) {
+            let value = row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2];
+            *channel = linear_to_srgb(value);
+        }
+        Color::new(channels[0], channels[1], channels[2])
+    }
+}
+
+fn srgb_to_linear(channel: u8) -> f64 {
+    let c = channel as f64 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f64) -> u8 {
+    let v = value.clamp(0.0, 1.0);
+    let c = if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0).round() as u8
+}
+
+/// How the distance between two colors is measured.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum ColorMetric {
+    /// Straight-line distance in RGB. The default.
+    #[default]
+    Rgb,
+    /// Delta E in CIELAB, which follows how different colors look.
+    Cielab,
+}
+
+impl ColorMetric {
+    pub fn distance(&self, a: &Color, b: &Color) -> f64 {
+        match self {
+            ColorMetric::Rgb => a.distance(b),
+            ColorMetric::Cielab => a.delta_e(b),
+        }
+    }
+}
+
+impl FromStr for ColorMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rgb" => Ok(ColorMetric::Rgb),
+            "cielab" => Ok(ColorMetric::Cielab),
+            _ => Err(format!(
+                "Unknown color metric {}; expected rgb or cielab",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ColorMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorMetric::Rgb => write!(f, "rgb"),
+            ColorMetric::Cielab => write!(f, "cielab"),
+        }
+    }
+}
+
+/// Color-vision deficiencies we simulate when checking that artist colors stay distinguishable.
+#[derive(Debug, Copy, Clone)]
+pub enum Deficiency {
+    Protanopia,
+    Deuteranopia,
+}
+
+impl fmt::Display for Deficiency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Deficiency::Protanopia => write!(f, "protanopia"),
+            Deficiency::Deuteranopia => write!(f, "deuteranopia"),
+        }
+    }
+}
+
+impl Deficiency {
+    // full-severity simulation matrices over linear RGB, from Machado et al. (2009)
+    fn matrix(&self) -> [[f64; 3]; 3] {
+        match self {
+            Deficiency::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            Deficiency::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+        }
+    }
+}
+
+/// Ways a simulation might derive an artist's color from its thread id, each
+/// taking the low 24 bits of an id hash as `0xRRGGBB`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColorDerivation {
+    /// 64-bit FNV-1a over the id's 8 little-endian bytes.
+    Fnv,
+    /// 32-bit FNV-1a over the low 32 bits of the id, as 4 little-endian bytes.
+    Fnv32,
+}
+
+impl ColorDerivation {
+    /// The color an artist with this id should have painted with.
+    pub fn color_for(&self, artist: Artist) -> Color {
+        let hash = match self {
+            ColorDerivation::Fnv => fnv1a(&artist.to_le_bytes()),
+            ColorDerivation::Fnv32 => {
+                let mut hash: u32 = 0x811c9dc5;
+                for byte in (artist as u32).to_le_bytes().iter() {
+                    hash ^= *byte as u32;
+                    hash = hash.wrapping_mul(0x01000193);
+                }
+                hash as u64
+            }
+        };
+        Color::new((hash >> 16) as u8, (hash >> 8) as u8, hash as u8)
+    }
+}
+
+/// 64-bit FNV-1a over `bytes`, the hash behind `ColorDerivation::Fnv` and
+/// the order pseudonyms are handed out in.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+impl FromStr for ColorDerivation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fnv" => Ok(ColorDerivation::Fnv),
+            "fnv32" => Ok(ColorDerivation::Fnv32),
+            _ => Err(format!(
+                "Unknown color derivation {}; expected fnv or fnv32",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ColorDerivation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorDerivation::Fnv => write!(f, "fnv"),
+            ColorDerivation::Fnv32 => write!(f, "fnv32"),
+        }
+    }
+}
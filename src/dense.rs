@@ -0,0 +1,106 @@
+use crate::canvas::{Artist, BoundingBox, Canvas, Point};
+use crate::color::Color;
+
+/// A canvas stored as one flat grid cell per coordinate inside its bounding
+/// box, holding the artist and color that painted it last.
+///
+/// `Canvas` keeps every log line, repaints and timestamps included, and costs
+/// the same no matter how spread out the pixels are, so it is the better fit
+/// for sparse logs and anything that cares about order. When the pixels tile
+/// most of a `W`x`H` box, a `DenseCanvas` is several times smaller and
+/// looking up or scanning coordinates walks memory in order instead of
+/// hashing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DenseCanvas {
+    origin: Point,
+    width: usize,
+    height: usize,
+    cells: Vec<Option<(Artist, Color)>>,
+}
+
+impl DenseCanvas {
+    /// Lays the canvas out over its bounding box; later pixels replace
+    /// earlier ones on the same coordinate.
+    pub fn from_canvas(canvas: &Canvas) -> DenseCanvas {
+        let bounds = match canvas.bounds() {
+            Some(bounds) => bounds,
+            None => {
+                return DenseCanvas {
+                    origin: Point::new(0, 0),
+                    width: 0,
+                    height: 0,
+                    cells: Vec::new(),
+                }
+            }
+        };
+        let mut dense = DenseCanvas {
+            origin: bounds.min,
+            width: bounds.width() as usize,
+            height: bounds.height() as usize,
+            cells: vec![None; bounds.width() as usize * bounds.height() as usize],
+        };
+        for pixel in canvas {
+            let index = dense
+                .index(pixel.coord)
+                .expect("pixel is inside the bounds");
+            dense.cells[index] = Some((pixel.artist, pixel.color));
+        }
+        dense
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The box the grid covers, or `None` for an empty canvas.
+    pub fn bounds(&self) -> Option<BoundingBox> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        let mut bounds = BoundingBox::new(self.origin);
+        bounds.include(Point::new(
+            (self.origin.x as i32 + self.width as i32 - 1) as i16,
+            (self.origin.y as i32 + self.height as i32 - 1) as i16,
+        ));
+        Some(bounds)
+    }
+
+    /// Who painted `point` last and with what color, if anyone did.
+    pub fn get(&self, point: Point) -> Option<(Artist, Color)> {
+        self.index(point).and_then(|index| self.cells[index])
+    }
+
+    /// Every cell of the grid in row-major order, painted or not.
+    pub fn cells(&self) -> impl Iterator<Item = (Point, Option<(Artist, Color)>)> + '_ {
+        self.cells.iter().enumerate().map(move |(index, cell)| {
+            let x = self.origin.x as i32 + (index % self.width) as i32;
+            let y = self.origin.y as i32 + (index / self.width) as i32;
+            (Point::new(x as i16, y as i16), *cell)
+        })
+    }
+
+    /// How many cells of the grid were painted.
+    pub fn painted(&self) -> usize {
+        self.cells.iter().filter(|cell| cell.is_some()).count()
+    }
+
+    // position of `point` in `cells`, indexed by y * width + x from the origin
+    fn index(&self, point: Point) -> Option<usize> {
+        let x = point.x as i32 - self.origin.x as i32;
+        let y = point.y as i32 - self.origin.y as i32;
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        Some(y as usize * self.width + x as usize)
+    }
+}
+
+impl From<&Canvas> for DenseCanvas {
+    fn from(canvas: &Canvas) -> DenseCanvas {
+        DenseCanvas::from_canvas(canvas)
+    }
+}
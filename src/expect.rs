@@ -0,0 +1,136 @@
+//! Expectations for a log written as JSON, for grading a run against a spec
+//! instead of a long command line.
+//!
+//! Every field is optional:
+//!
+//! ```json
+//! {
+//!     "artists": 54,
+//!     "min_pixels": 1,
+//!     "max_pixels": 500,
+//!     "palette": [[255, 0, 0], [0, 0, 255]],
+//!     "width": 100,
+//!     "height": 100,
+//!     "checks": ["overlap", "holes"]
+//! }
+//! ```
+
+use std::collections::HashSet;
+use std::io::{self, Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::color::Color;
+use crate::report::{write_json, Summary};
+
+/// What a log is expected to look like. Each expectation is judged by one
+/// check and `checks` names any others to run; no checks run besides those.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Expectations {
+    /// How many artists should paint.
+    pub artists: Option<usize>,
+    /// The fewest pixels any one artist may paint.
+    pub min_pixels: Option<usize>,
+    /// The most pixels any one artist may paint.
+    pub max_pixels: Option<usize>,
+    /// The `[r, g, b]` colors artists must pick from.
+    pub palette: Option<Vec<[u8; 3]>>,
+    /// The size of the canvas, which should be painted in full. Either both
+    /// or neither.
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    /// Checks to run, by the names `--only` takes.
+    pub checks: Option<Vec<String>>,
+}
+
+impl Expectations {
+    /// Reads expectations from JSON, rejecting unknown fields so a typo
+    /// doesn't silently go unchecked.
+    pub fn from_reader(reader: impl Read) -> Result<Expectations, String> {
+        let expectations: Expectations =
+            serde_json::from_reader(reader).map_err(|e| format!("Invalid expectations: {}", e))?;
+        if expectations.width.is_some() != expectations.height.is_some() {
+            return Err("Invalid expectations: width and height go together".to_string());
+        }
+        Ok(expectations)
+    }
+
+    pub fn palette_colors(&self) -> Option<HashSet<Color>> {
+        let palette = self.palette.as_ref()?;
+        Some(
+            palette
+                .iter()
+                .map(|[r, g, b]| Color::new(*r, *g, *b))
+                .collect(),
+        )
+    }
+
+    /// Each expectation that was given with the check that judges it, as
+    /// `(expectation, check)`, in the order the checks run.
+    pub fn judged_by(&self) -> Vec<(&'static str, &'static str)> {
+        let mut judged = Vec::new();
+        if self.width.is_some() {
+            judged.push(("width and height", "full"));
+        }
+        if self.artists.is_some() {
+            judged.push(("artists", "artist-count"));
+        }
+        if self.min_pixels.is_some() {
+            judged.push(("min_pixels", "all-draw"));
+        }
+        if self.max_pixels.is_some() {
+            judged.push(("max_pixels", "max-pixels"));
+        }
+        if self.palette.is_some() {
+            judged.push(("palette", "palette"));
+        }
+        judged
+    }
+}
+
+/// How a log measured up to its expectations.
+#[derive(Debug, Serialize)]
+pub struct Validation {
+    pub passed: bool,
+    /// The expectations the log didn't meet, with the check that failed.
+    pub unmet: Vec<Unmet>,
+    pub summary: Summary,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Unmet {
+    /// The field of the expectations, or `checks` for a check named there.
+    pub expectation: &'static str,
+    pub check: &'static str,
+}
+
+impl Validation {
+    /// Matches every failed check in `summary` up with the expectation that
+    /// asked for it.
+    pub fn new(expectations: &Expectations, summary: Summary) -> Validation {
+        let judged = expectations.judged_by();
+        let unmet: Vec<Unmet> = summary
+            .failed
+            .iter()
+            .map(|check| Unmet {
+                expectation: judged
+                    .iter()
+                    .find(|(_, judging)| judging == check)
+                    .map_or("checks", |(expectation, _)| *expectation),
+                check,
+            })
+            .collect();
+        Validation {
+            passed: summary.passed,
+            unmet,
+            summary,
+        }
+    }
+}
+
+/// Writes the validation as JSON, pretty-printed as `term::json_pretty` says.
+pub fn write_validation(validation: &Validation, out: &mut impl Write) -> io::Result<()> {
+    write_json(validation, out)?;
+    writeln!(out)
+}
@@ -0,0 +1,101 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::{self, Write};
+
+use crate::anon::name;
+use crate::canvas::{Artist, Canvas, Point};
+use crate::color::Color;
+use crate::report::artist_reports;
+
+/// Pairs of artists whose pixels share an edge, each pair listed once with the
+/// lower id first.
+pub fn adjacent_artists(canvas: &Canvas) -> BTreeSet<(Artist, Artist)> {
+    let mut painters: HashMap<Point, Vec<Artist>> = HashMap::new();
+    for pixel in canvas {
+        painters.entry(pixel.coord).or_default().push(pixel.artist);
+    }
+
+    let mut pairs = BTreeSet::new();
+    for pixel in canvas {
+        for neighbor in pixel.coord.neighbors().iter() {
+            for other in painters.get(neighbor).into_iter().flatten() {
+                if *other != pixel.artist {
+                    pairs.insert((pixel.artist.min(*other), pixel.artist.max(*other)));
+                }
+            }
+        }
+    }
+    pairs
+}
+
+/// Writes the artist adjacency graph in Graphviz DOT format, with each node
+/// filled in its artist's color.
+pub fn write_adjacency_dot(
+    canvas: &Canvas,
+    colors_map: &HashMap<Artist, Color>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    writeln!(out, "graph artists {{")?;
+    writeln!(out, "    node [style=filled];")?;
+    let mut artists: Vec<(&Artist, &Color)> = colors_map.iter().collect();
+    artists.sort_by_key(|(artist, _)| **artist);
+    for (artist, color) in artists {
+        // keep the label readable on dark fills
+        let font = if color.luminance() < 128.0 {
+            "white"
+        } else {
+            "black"
+        };
+        writeln!(
+            out,
+            "    \"{}\" [fillcolor=\"#{:02x}{:02x}{:02x}\", fontcolor={}];",
+            name(*artist),
+            color.r,
+            color.g,
+            color.b,
+            font
+        )?;
+    }
+    for (artist, other) in adjacent_artists(canvas) {
+        writeln!(out, "    \"{}\" -- \"{}\";", name(artist), name(other))?;
+    }
+    writeln!(out, "}}")
+}
+
+/// Writes one CSV row per artist, in id order, after a header row: the
+/// pixels it painted, its bounding box, its color as `#rrggbb`, the centroid
+/// of its points, and how many pieces its region is in.
+pub fn write_stats_csv(
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    colors_map: &HashMap<Artist, Color>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    writeln!(
+        out,
+        "artist,pixels,min_x,min_y,max_x,max_y,color,centroid_x,centroid_y,islands"
+    )?;
+    for report in artist_reports(posns_map, colors_map) {
+        let points = &posns_map[&report.id];
+        let n = points.len() as f64;
+        let centroid_x = points.iter().map(|p| p.x as f64).sum::<f64>() / n;
+        let centroid_y = points.iter().map(|p| p.y as f64).sum::<f64>() / n;
+        let color = report
+            .color
+            .map(|c| format!("#{:02x}{:02x}{:02x}", c.r, c.g, c.b))
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{:.2},{:.2},{}",
+            name(report.id),
+            report.pixels,
+            report.bounds.min.x,
+            report.bounds.min.y,
+            report.bounds.max.x,
+            report.bounds.max.y,
+            color,
+            centroid_x,
+            centroid_y,
+            report.islands
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,27 @@
+pub mod anon;
+pub mod binary;
+mod canvas;
+pub mod checks;
+mod color;
+mod dense;
+pub mod expect;
+pub mod export;
+mod parse;
+pub mod patterns;
+pub mod regions;
+pub mod registry;
+pub mod render;
+pub mod repl;
+pub mod report;
+pub mod stats;
+pub mod term;
+
+pub use canvas::{Artist, BoundingBox, Canvas, CanvasPixel, Origin, Point};
+pub use color::{Color, ColorDerivation, ColorMetric, Deficiency};
+pub use dense::DenseCanvas;
+pub use parse::{
+    parse_line, parse_line_with, parse_marked_line, read_log_to_canvas, read_log_with_summary,
+    read_palette, read_points, write_log, write_log_with_seq, ColorFormat, LineError, LineFormat,
+    LineRange, LockMarker, LogError, ParseError, ParseOptions, ParseSummary, SkippedPaint,
+    Truncation, DEFAULT_MAX_LINE_LENGTH,
+};
@@ -1,122 +1,679 @@
 use std::cmp::{max, min};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::error::Error;
-use std::fmt;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufReader, Write};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-type Canvas = Vec<CanvasPixel>;
-type Artist = u32;
+use log_reader::anon::{self, name, Pseudonyms};
+use log_reader::binary::{is_binary, read_binary, write_binary};
+use log_reader::checks::{CheckResults, OffenderList, SelfOverlap};
+use log_reader::expect::{write_validation, Expectations, Validation};
+use log_reader::export::{write_adjacency_dot, write_stats_csv};
+use log_reader::patterns::{best_alignment, side_by_side};
+use log_reader::registry::{AnalysisContext, CheckConfig, Registry};
+use log_reader::render::{
+    render, render_aligned, render_heatmap, OverlapResolve, RenderOptions, BACKGROUND,
+};
+use log_reader::repl::run_repl;
+use log_reader::report::{
+    artist_reports, run_summary, write_batch_json, write_ndjson, write_posns_json, FileReport,
+    Record, ReportFormat, Summary,
+};
+use log_reader::stats::{
+    coverage, print_artist_stats, print_color_stats, print_completion_order, print_density,
+    print_first_and_last, print_interleaving, print_paint_counts, print_quadrants, print_runs,
+    print_skipped_paints, single_pixel_artists, QUADRANT_MIN_FRACTION,
+};
+use log_reader::term::{
+    green, red, set_color_allowed, set_deterministic, set_hold_warnings, set_human_to_stderr,
+    set_json_pretty,
+};
+use log_reader::{
+    read_log_to_canvas, read_log_with_summary, read_palette, read_points, write_log,
+    write_log_with_seq, Artist, BoundingBox, Canvas, Color, ColorDerivation, ColorMetric,
+    LineRange, Origin, ParseError, ParseOptions, ParseSummary, Point,
+};
+use log_reader::{say, warn};
 
-#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
-struct CanvasPixel {
-    artist: Artist,
-    coord: Point,
-    color: Color,
+// command line options; the first non-flag argument is the log file, or a
+// directory of them
+#[derive(Default)]
+struct Options {
+    filepath: String,
+    // cap on how many individual offenders each check lists
+    top_n: Option<usize>,
+    // print per-artist shape statistics
+    stats: bool,
+    // print statistics about the colors artists used
+    color_stats: bool,
+    // print artists in the order they painted their last pixel
+    completion_order: bool,
+    // size of the cells to report the densest and sparsest of
+    density: Option<usize>,
+    // print how many pixels each quadrant of the canvas holds
+    quadrants: bool,
+    // how far under the average a quadrant may fall before a warning
+    quadrant_min: Option<f64>,
+    // print each artist's pixels as horizontal runs
+    runs: bool,
+    // print pairs of artists whose territories interleave
+    interleaving: bool,
+    // check that artist colors stay distinguishable under color-vision deficiencies
+    check_cvd: bool,
+    // fail when the number of colors and artists differ, not just warn
+    require_color_per_artist: bool,
+    // how the color-vision check measures how far apart colors are
+    color_metric: ColorMetric,
+    // check that no artist paints in a shade of gray
+    check_grayscale: bool,
+    // how artist colors should follow from their ids, when they should
+    color_from: Option<ColorDerivation>,
+    // check that each color is one connected region
+    check_color_regions: bool,
+    // check for pixels far from the main drawing
+    check_outliers: bool,
+    // how far outside the main drawing a pixel may be before it is an outlier
+    outlier_distance: Option<f64>,
+    // check that no artist's region encloses cells it did not paint
+    check_holes: bool,
+    // check for artists whose patterns mirror each other
+    check_mirrors: bool,
+    // check for artists taking the same run of moves, as from a shared RNG
+    check_shared_rng: bool,
+    // how many matching moves in a row count as a shared RNG
+    rng_run: Option<usize>,
+    // warn about artists painting every Nth pixel
+    check_lattice: bool,
+    // check for one thread's pixels logged under two artist ids
+    check_double_logged: bool,
+    // check timestamped logs for points aliased by coordinate truncation
+    check_aliasing: bool,
+    // check timestamped logs for pauses that suggest dropped lines
+    check_gaps: bool,
+    // the exact set of artist ids expected to appear in the log
+    artist_ids: Option<HashSet<Artist>>,
+    // the first artist id in a run of ids with no gaps
+    id_base: Option<Artist>,
+    // the inclusive range real thread ids fall in
+    tid_range: Option<(Artist, Artist)>,
+    // the size of the canvas the simulation was meant to paint
+    width: Option<usize>,
+    height: Option<usize>,
+    // fail unless every pixel of the canvas was painted
+    require_full: bool,
+    // the width:height ratio the drawing should have
+    expect_aspect: Option<f64>,
+    // the exact number of pixels the log should contain
+    expect_pixels: Option<usize>,
+    // how many overlapping positions the overlap check tolerates
+    max_overlaps: usize,
+    // whether an artist repainting its own pixel is ignored, listed, or fails
+    self_overlap: SelfOverlap,
+    // the most pixels any one artist may draw
+    max_pixels: Option<usize>,
+    // count distinct coordinates rather than lines for expect_pixels
+    dedupe_pixels: bool,
+    // file of `x y` coordinates that no artist may paint on
+    protected: Option<String>,
+    // file of `r g b` colors that artists must pick from
+    palette: Option<String>,
+    // the palette itself, when it comes from validate's expectations
+    palette_colors: Option<HashSet<Color>>,
+    // how many artists should paint, 54 unless validate's expectations say
+    artist_count: Option<usize>,
+    // the fewest pixels any one artist may draw, 1 unless validate's
+    // expectations say
+    min_pixels: Option<usize>,
+    // how to interpret log lines
+    parse: ParseOptions,
+    // warn when fewer artists than this paint
+    min_artists: Option<usize>,
+    // file to write per-line parse errors to, instead of stderr
+    error_log: Option<String>,
+    // file to write the artist adjacency graph to, in Graphviz DOT format
+    dot: Option<String>,
+    // file to write posns_map to, as JSON
+    dump_map: Option<String>,
+    // file to write a row of stats per artist to, as CSV
+    stats_csv: Option<String>,
+    // file to draw the canvas to, as a PPM image
+    render: Option<String>,
+    // file to draw how many artists painted each cell to, as a PPM image
+    heatmap: Option<String>,
+    // how to draw the canvas
+    render_options: RenderOptions,
+    // answer questions about the log interactively once the checks are done
+    repl: bool,
+    // how to write the results to stdout
+    format: ReportFormat,
+    // never print ANSI colors, even to a terminal
+    no_color: bool,
+    // print everything in a stable order, for diffing runs
+    deterministic: bool,
+    // pretty-print (or not) JSON output, instead of following stdout
+    json_pretty: Option<bool>,
+    // refuse to read the log through a symlink
+    no_follow_symlinks: bool,
+    // print why each check exists before running it
+    explain: bool,
+    // warn when the log's last line looks cut off mid-write
+    check_truncation: bool,
+    // hold errors and warnings back for sections at the end
+    by_severity: bool,
+    // print pseudonyms instead of artist ids
+    anonymize: bool,
+    // file to write which pseudonym stands for which id to
+    anonymize_map: Option<String>,
+    // file to write a JSON report of every log to, when given a directory
+    batch_json: Option<String>,
+    // run only these checks, when given
+    only: Option<HashSet<&'static str>>,
+    // checks not to run
+    skip: HashSet<&'static str>,
 }
 
-impl CanvasPixel {
-    fn new(artist: Artist, x: i16, y: i16, red: u8, green: u8, blue: u8) -> CanvasPixel {
-        CanvasPixel {
-            artist,
-            coord: Point::new(x, y),
-            color: Color::new(red, green, blue),
+fn parse_args(args: Vec<String>) -> Result<Options, String> {
+    let mut args = args.into_iter();
+    let mut options = Options::default();
+    let mut filepath = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--top-n" => options.top_n = Some(flag_value(&mut args, "--top-n")?),
+            "--stats" => options.stats = true,
+            "--color-stats" => options.color_stats = true,
+            "--completion-order" => options.completion_order = true,
+            "--interleaving" => options.interleaving = true,
+            "--runs" => options.runs = true,
+            "--density" => options.density = Some(flag_value(&mut args, "--density")?),
+            "--quadrants" => options.quadrants = true,
+            "--quadrant-min" => {
+                options.quadrant_min = Some(flag_value(&mut args, "--quadrant-min")?)
+            }
+            "--check-cvd" => options.check_cvd = true,
+            "--check-grayscale" => options.check_grayscale = true,
+            "--color-metric" => options.color_metric = flag_value(&mut args, "--color-metric")?,
+            "--require-color-per-artist" => options.require_color_per_artist = true,
+            "--check-holes" => options.check_holes = true,
+            "--check-color-regions" => options.check_color_regions = true,
+            "--check-outliers" => options.check_outliers = true,
+            "--outlier-distance" => {
+                options.outlier_distance = Some(flag_value(&mut args, "--outlier-distance")?)
+            }
+            "--color-from" => options.color_from = Some(flag_value(&mut args, "--color-from")?),
+            "--check-mirrors" => options.check_mirrors = true,
+            "--check-shared-rng" => options.check_shared_rng = true,
+            "--rng-run" => options.rng_run = Some(flag_value(&mut args, "--rng-run")?),
+            "--check-lattice" => options.check_lattice = true,
+            "--check-double-logged" => options.check_double_logged = true,
+            "--check-aliasing" => options.check_aliasing = true,
+            "--check-gaps" => options.check_gaps = true,
+            "--artist-ids" => {
+                let ids = flag_value::<String>(&mut args, "--artist-ids")?;
+                options.artist_ids = Some(parse_artist_ids(&ids)?);
+            }
+            "--width" => options.width = Some(flag_value(&mut args, "--width")?),
+            "--height" => options.height = Some(flag_value(&mut args, "--height")?),
+            "--require-full" => options.require_full = true,
+            "--expect-aspect" => {
+                let ratio = flag_value::<String>(&mut args, "--expect-aspect")?;
+                options.expect_aspect = Some(parse_aspect(&ratio)?);
+            }
+            "--id-base" => options.id_base = Some(flag_value(&mut args, "--id-base")?),
+            "--tid-range" => {
+                let range = flag_value::<String>(&mut args, "--tid-range")?;
+                options.tid_range = Some(parse_tid_range(&range)?);
+            }
+            "--expect-pixels" => {
+                options.expect_pixels = Some(flag_value(&mut args, "--expect-pixels")?)
+            }
+            "--dedupe-pixels" => options.dedupe_pixels = true,
+            "--self-overlap" => options.self_overlap = flag_value(&mut args, "--self-overlap")?,
+            "--max-overlaps" => options.max_overlaps = flag_value(&mut args, "--max-overlaps")?,
+            "--max-pixels" => options.max_pixels = Some(flag_value(&mut args, "--max-pixels")?),
+            "--protected" => options.protected = Some(flag_value(&mut args, "--protected")?),
+            "--palette" => options.palette = Some(flag_value(&mut args, "--palette")?),
+            "--lenient" => options.parse.lenient = true,
+            "--default-missing" => options.parse.default_missing = true,
+            "--with-timestamp" => options.parse.with_timestamp = true,
+            "--lines" => {
+                let range = flag_value::<String>(&mut args, "--lines")?;
+                options.parse.lines = Some(range.parse::<LineRange>()?);
+            }
+            "--max-line-length" => {
+                options.parse.max_line_length = flag_value(&mut args, "--max-line-length")?
+            }
+            "--retry-on-lock-marker" => options.parse.lock_markers = true,
+            "--max-pixels-memory" => {
+                options.parse.pixel_budget = Some(flag_value(&mut args, "--max-pixels-memory")?)
+            }
+            "--input-format" => {
+                options.parse.line_format = flag_value(&mut args, "--input-format")?
+            }
+            "--color-format" => {
+                options.parse.color_format = flag_value(&mut args, "--color-format")?
+            }
+            "--dot" => options.dot = Some(flag_value(&mut args, "--dot")?),
+            "--min-artists" => options.min_artists = Some(flag_value(&mut args, "--min-artists")?),
+            "--dump-map" => options.dump_map = Some(flag_value(&mut args, "--dump-map")?),
+            "--stats-csv" => options.stats_csv = Some(flag_value(&mut args, "--stats-csv")?),
+            "--render" => options.render = Some(flag_value(&mut args, "--render")?),
+            "--heatmap" => options.heatmap = Some(flag_value(&mut args, "--heatmap")?),
+            "--origin" => options.render_options.origin = flag_value(&mut args, "--origin")?,
+            "--blend" => options.render_options.blend = true,
+            "--overlap-resolve" => {
+                options.render_options.resolve = flag_value(&mut args, "--overlap-resolve")?
+            }
+            "--repl" => options.repl = true,
+            "--format" => options.format = flag_value(&mut args, "--format")?,
+            "--no-color" => options.no_color = true,
+            "--deterministic" => options.deterministic = true,
+            "--json-pretty" => options.json_pretty = Some(true),
+            "--json-compact" => options.json_pretty = Some(false),
+            "--no-follow-symlinks" => options.no_follow_symlinks = true,
+            "--explain" => options.explain = true,
+            "--by-severity" => options.by_severity = true,
+            "--check-truncation" => options.check_truncation = true,
+            "--anonymize" => options.anonymize = true,
+            "--anonymize-map" => {
+                options.anonymize_map = Some(flag_value(&mut args, "--anonymize-map")?)
+            }
+            "--only" => {
+                let names = flag_value::<String>(&mut args, "--only")?;
+                options.only = Some(parse_check_names(&names, "--only")?);
+            }
+            "--skip" => {
+                let names = flag_value::<String>(&mut args, "--skip")?;
+                options.skip.extend(parse_check_names(&names, "--skip")?);
+            }
+            "--batch-json" => options.batch_json = Some(flag_value(&mut args, "--batch-json")?),
+            "--error-log" => options.error_log = Some(flag_value(&mut args, "--error-log")?),
+            _ if arg.starts_with("--") => return Err(format!("Unknown option {}", arg)),
+            _ => filepath = Some(arg),
         }
     }
-}
 
-#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
-struct Color {
-    r: u8,
-    g: u8,
-    b: u8,
-}
+    if options.check_aliasing && !options.parse.with_timestamp {
+        return Err("--check-aliasing needs timestamps; pass --with-timestamp too".to_string());
+    }
+    if options.check_gaps && !options.parse.with_timestamp {
+        return Err("--check-gaps needs timestamps; pass --with-timestamp too".to_string());
+    }
 
-impl fmt::Display for Color {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "({}, {}, {})", self.r, self.g, self.b)
+    if options.render_options.blend && options.render_options.resolve != OverlapResolve::Last {
+        return Err("--blend averages every artist on a cell, so it can't be combined with --overlap-resolve".to_string());
+    }
+    // the heatmap is only ever a PPM, whatever the file is called
+    if let Some(path) = &options.heatmap {
+        if !path.to_ascii_lowercase().ends_with(".ppm") {
+            return Err(format!(
+                "--heatmap writes a PPM image; name the file with a .ppm extension instead of {}",
+                path
+            ));
+        }
+    }
+    if options.anonymize_map.is_some() && !options.anonymize {
+        return Err("--anonymize-map only makes sense with --anonymize".to_string());
     }
-}
 
-impl Color {
-    fn new(r: u8, g: u8, b: u8) -> Color {
-        Color { r, g, b }
+    if options.width.is_some() != options.height.is_some() {
+        return Err("--width and --height have to be given together".to_string());
+    }
+    if options.require_full && options.width.is_none() {
+        return Err("--require-full needs the canvas size; pass --width and --height".to_string());
+    }
+
+    if options.by_severity {
+        set_hold_warnings(true);
+    }
+
+    // keep stdout for the records from here on
+    if options.format == ReportFormat::Ndjson {
+        set_human_to_stderr(true);
     }
-}
 
-#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
-struct Point {
-    x: i16,
-    y: i16,
+    options.filepath = if let Some(file) = filepath {
+        say!("{}", file);
+        file
+    } else {
+        String::from("../canvas.log")
+    };
+
+    Ok(options)
 }
 
-impl Point {
-    fn new(x: i16, y: i16) -> Point {
-        Point { x, y }
+// comma separated ids and inclusive ranges, e.g. `1,2,10-20`
+fn parse_artist_ids(list: &str) -> Result<HashSet<Artist>, String> {
+    let mut ids = HashSet::new();
+    for part in list.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let invalid = || format!("Invalid artist id or range in --artist-ids: {}", part);
+        match part.split_once('-') {
+            Some((lo, hi)) => {
+                let lo = lo.trim().parse::<Artist>().map_err(|_| invalid())?;
+                let hi = hi.trim().parse::<Artist>().map_err(|_| invalid())?;
+                if lo > hi {
+                    return Err(invalid());
+                }
+                ids.extend(lo..=hi);
+            }
+            None => {
+                ids.insert(part.parse::<Artist>().map_err(|_| invalid())?);
+            }
+        }
     }
+    Ok(ids)
 }
 
-// similarly here, ordering by rightmost point
-impl PartialOrd for Point {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+// an inclusive `lo,hi` range of thread ids
+fn parse_tid_range(range: &str) -> Result<(Artist, Artist), String> {
+    let invalid = || format!("Invalid thread id range for --tid-range: {}", range);
+    let (lo, hi) = range.split_once(',').ok_or_else(invalid)?;
+    let lo = lo.trim().parse::<Artist>().map_err(|_| invalid())?;
+    let hi = hi.trim().parse::<Artist>().map_err(|_| invalid())?;
+    if lo > hi {
+        return Err(invalid());
     }
+    Ok((lo, hi))
 }
 
-// trick here: we just care about *some* relative ordering, so ordering by rightmost point.
-impl Ord for Point {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.x.cmp(&other.x)
+// a width:height ratio, either as `4:3` or as a single number like `1.5`
+fn parse_aspect(ratio: &str) -> Result<f64, String> {
+    let invalid = || format!("Invalid aspect ratio for --expect-aspect: {}", ratio);
+    let value = match ratio.split_once(':') {
+        Some((w, h)) => {
+            let w = w.trim().parse::<f64>().map_err(|_| invalid())?;
+            let h = h.trim().parse::<f64>().map_err(|_| invalid())?;
+            w / h
+        }
+        None => ratio.trim().parse::<f64>().map_err(|_| invalid())?,
+    };
+    if value.is_finite() && value > 0.0 {
+        Ok(value)
+    } else {
+        Err(invalid())
     }
 }
 
+// comma separated check names, e.g. `overlap,colors`, as the registry the
+// binary runs knows them
+fn parse_check_names(list: &str, flag: &str) -> Result<HashSet<&'static str>, String> {
+    Registry::builtin().parse_names(list, flag)
+}
+
+fn flag_value<T: FromStr>(
+    args: &mut impl Iterator<Item = String>,
+    flag: &str,
+) -> Result<T, String> {
+    let value = args
+        .next()
+        .ok_or_else(|| format!("Missing value for {}", flag))?;
+    value
+        .parse::<T>()
+        .map_err(|_| format!("Invalid value for {}: {}", flag, value))
+}
+
 // TODO
-// allow specifying file name
 // allow default size to analyze
 fn main() -> Result<(), Box<dyn Error>> {
-    let mut args = std::env::args();
-    args.next();
-    let filepath = if let Some(file) = args.next() {
-        println!("{}", file);
-        file
-    } else {
-        String::from("../canvas.log")
-    };
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("crop") => return crop(&args[1..]),
+        Some("extract") => return extract(&args[1..]),
+        Some("compare-artists") => return compare_artists(&args[1..]),
+        Some("canonicalize") => return canonicalize(&args[1..]),
+        Some("save-bin") => return save_bin(&args[1..]),
+        Some("load-bin") => return load_bin(&args[1..]),
+        Some("validate") => return validate(&args[1..]),
+        _ => (),
+    }
+
+    let options = parse_args(args)?;
+    if options.no_color {
+        set_color_allowed(false);
+    }
+    set_deterministic(options.deterministic);
+    set_json_pretty(options.json_pretty);
 
-    let logfile = match File::open(&filepath) {
+    if Path::new(&options.filepath).is_dir() {
+        return analyze_dir(&options);
+    }
+    let summary = analyze(&options, &options.filepath)?;
+    if !summary.passed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+// runs every check on every `*.log` in the directory, carrying on past logs
+// that fail or can't be read, then prints how each one did
+fn analyze_dir(options: &Options) -> Result<(), Box<dyn Error>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(&options.filepath)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "log") && !path.is_dir());
+    paths.sort();
+    if paths.is_empty() {
+        return Err(format!("No *.log files found in {}.", options.filepath).into());
+    }
+
+    let mut reports: BTreeMap<String, FileReport> = BTreeMap::new();
+    for path in &paths {
+        let filepath = path.display().to_string();
+        say!("=== {} ===", filepath);
+        let report = match analyze(options, &filepath) {
+            Ok(summary) => FileReport::Analyzed(summary),
+            Err(err) => {
+                eprintln!("Could not analyze {}: {}", filepath, err);
+                FileReport::Failed {
+                    error: err.to_string(),
+                }
+            }
+        };
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        reports.insert(name.into_owned(), report);
+    }
+
+    say!("Batch results:");
+    let width = reports.keys().map(String::len).max().unwrap_or(0);
+    let mut passed = 0;
+    for (name, report) in &reports {
+        let outcome = match report {
+            FileReport::Analyzed(summary) if summary.passed => {
+                passed += 1;
+                green("PASS")
+            }
+            FileReport::Analyzed(summary) => {
+                format!("{} ({})", red("FAIL"), summary.failed.join(", "))
+            }
+            FileReport::Failed { error } => format!("{} ({})", red("ERROR"), error),
+        };
+        say!("{:<width$}  {}", name, outcome, width = width);
+    }
+    say!("{} of {} logs passed.", passed, reports.len());
+
+    if let Some(path) = &options.batch_json {
+        let mut file = io::BufWriter::new(File::create(path)?);
+        write_batch_json(&reports, &mut file)?;
+        file.flush()?;
+        say!("Wrote the combined report to {}.", path);
+    }
+
+    if passed < reports.len() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+// fewer distinct coordinates per pixel than this and the log is nearly all
+// repaints of a handful of cells, like a simulation stuck writing (0, 0)
+const MIN_DISTINCT_RATIO: f64 = 0.05;
+
+// fewer artists than this and the checks between artists have nothing to
+// compare, like a simulation that never spawned its threads
+const MIN_ARTISTS: usize = 2;
+
+// the whole pipeline for one log: parse it, print what's asked for, and run
+// every check that applies
+fn analyze(options: &Options, filepath: &str) -> Result<Summary, Box<dyn Error>> {
+    let top_n = options.top_n;
+
+    if options.no_follow_symlinks {
+        // symlink_metadata looks at the link itself rather than its target
+        let is_link = std::fs::symlink_metadata(filepath)
+            .map(|meta| meta.file_type().is_symlink())
+            .unwrap_or(false);
+        if is_link {
+            return Err(format!(
+                "Refusing to read {}: it is a symlink and --no-follow-symlinks was given.",
+                filepath
+            )
+            .into());
+        }
+    }
+
+    let logfile = match File::open(filepath) {
         Ok(file) => {
-            println!("Successfully found log file {}.", filepath);
+            say!("Successfully found log file {}.", filepath);
             file
         }
         Err(data) => {
-            println!("File {} not found.", filepath);
+            say!("File {} not found.", filepath);
             return Err(data)?;
         }
     };
 
     // read the file data into out data structure
-    let log = BufReader::new(logfile);
+    let mut log = BufReader::new(logfile);
 
-    let log_lines = log
-        .lines()
-        .map(|l| l.expect("Error reading line.\n"))
-        .collect::<Vec<_>>();
+    let (canvas, summary) = if is_binary(&mut log)? {
+        let canvas = read_binary(&mut log)?;
+        let summary = ParseSummary {
+            bounds: canvas.bounds(),
+            ..ParseSummary::default()
+        };
+        (canvas, summary)
+    } else {
+        match read_log_with_summary(log, &options.parse) {
+            Err(e) if e.kind() == io::ErrorKind::OutOfMemory => {
+                return Err(format!(
+                    "Aborted reading the log: {}; raise --max-pixels-memory to read more.",
+                    e
+                )
+                .into())
+            }
+            read => read?,
+        }
+    };
+    let parse_errors = summary.errors;
+    let skipped = summary.skipped;
+    if summary.defaulted > 0 {
+        warn!(
+            "filled in missing color fields with 0 on {} lines.",
+            summary.defaulted
+        );
+    }
+    let unique = canvas
+        .iter()
+        .map(|p| p.coord)
+        .collect::<HashSet<Point>>()
+        .len();
+    say!(
+        "{} pixels were painted, on {} unique coordinates ({} repaints)",
+        canvas.len(),
+        unique,
+        canvas.len() - unique
+    );
+    // cheap enough to say before the expensive checks drown it out
+    let distinct_ratio = unique as f64 / canvas.len().max(1) as f64;
+    if !canvas.is_empty() && distinct_ratio < MIN_DISTINCT_RATIO {
+        warn!(
+            "only {:.3} distinct coordinates per pixel, under {}; the simulation is likely painting the same few cells over and over.",
+            distinct_ratio,
+            MIN_DISTINCT_RATIO
+        );
+    }
+    if let Some(bounds) = summary.bounds {
+        say!(
+            "The canvas spans {} ({}x{}, aspect ratio {:.2}:1)",
+            bounds,
+            bounds.width(),
+            bounds.height(),
+            bounds.width() as f64 / bounds.height() as f64
+        );
+    }
+    // before the parse errors, so a cut off last line isn't taken for a bad one
+    if let Some(truncation) = summary.truncated.filter(|_| options.check_truncation) {
+        warn!(
+            "{}; the log looks truncated, as if the simulation crashed partway through writing it, rather than logging the line wrong.",
+            truncation
+        );
+    }
+    if !parse_errors.is_empty() {
+        report_parse_errors(&parse_errors, options.error_log.as_deref())?;
+        if !options.parse.lenient {
+            return Err(
+                "Stopped reading the log at the first malformed line; pass --lenient to skip malformed lines."
+                    .into(),
+            );
+        }
+        eprintln!("Skipped {} malformed lines.", parse_errors.len());
+    }
 
-    let canvas = read_log_to_canvas(log_lines)?;
+    if let Some(range) = options.parse.lines {
+        warn!(
+            "only lines {} were read, so the pixel-count, artist-count, expected-artists, all-draw, and max-pixels checks are unreliable on this slice.",
+            range
+        );
+    }
+
+    let size = options.width.zip(options.height);
+    if let Some((width, height)) = size {
+        let (painted, total) = coverage(&canvas, width, height);
+        say!(
+            "{} of the {}x{} canvas's {} pixels were painted ({:.1}%)",
+            painted,
+            width,
+            height,
+            total,
+            100.0 * painted as f64 / total.max(1) as f64
+        );
+    }
+
+    // hide who is who from here on; expected artists get pseudonyms too, so
+    // the ones that never painted can still be told apart
+    if options.anonymize {
+        let expected = options.artist_ids.iter().flatten().copied();
+        let pseudonyms = Pseudonyms::new(canvas.iter().map(|p| p.artist).chain(expected));
+        if let Some(path) = &options.anonymize_map {
+            let mut file = File::create(path)?;
+            pseudonyms.write_mapping(&mut file)?;
+            eprintln!("Wrote the pseudonym mapping to {}.", path);
+        }
+        anon::install(pseudonyms);
+    }
+
+    let mut results = CheckResults::with_selection(options.only.clone(), options.skip.clone());
+    results.set_explain(options.explain);
+    results.set_by_severity(options.by_severity);
 
     // begin analysis
     // initialize all the useful data structures for analysis beforehand
     let mut posns_map: HashMap<Artist, HashSet<Point>> = HashMap::new();
-    println!("Initializing artist and color data...");
+    // the first color each artist painted with
+    let mut colors_map: HashMap<Artist, Color> = HashMap::new();
+    say!("Initializing artist and color data...");
+    let mut repaints = OffenderList::new(top_n);
     for pixel in &canvas {
+        colors_map.entry(pixel.artist).or_insert(pixel.color);
         match posns_map.get_mut(&pixel.artist) {
             Some(set) => {
                 let res = set.insert(pixel.coord);
-                if !res {
+                if !res && options.self_overlap == SelfOverlap::Warn && repaints.admit() {
                     eprintln!(
                         "Artist {} already painted at position {:#?}!",
-                        pixel.artist, pixel.coord
+                        name(pixel.artist),
+                        pixel.coord
                     );
                 }
             }
@@ -127,232 +684,565 @@ fn main() -> Result<(), Box<dyn Error>> {
             }
         }
     }
+    repaints.finish();
+    // stated on its own, so it's there even when the expected count is wrong
+    say!("Found {} distinct artists.", posns_map.len());
+    let min_artists = options.min_artists.unwrap_or(MIN_ARTISTS);
+    if !posns_map.is_empty() && posns_map.len() < min_artists {
+        warn!(
+            "only {} artists painted, under {}; checks between artists have little to compare and pass easily, so make sure the simulation spawned its threads.",
+            posns_map.len(),
+            min_artists
+        );
+    }
+    if options.parse.lock_markers {
+        print_skipped_paints(&skipped);
+    }
+
+    // a failure signature of its own, so it shows whatever the pixel threshold
+    let singles = single_pixel_artists(&posns_map);
+    if !singles.is_empty() {
+        warn!(
+            "{} artists painted a single pixel, which usually means their threads starved or failed.",
+            singles.len()
+        );
+        let mut offenders = OffenderList::new(top_n);
+        for (artist, point) in &singles {
+            if offenders.admit() {
+                warn!(
+                    "artist {} painted only ({}, {})",
+                    name(*artist),
+                    point.x,
+                    point.y
+                );
+            }
+        }
+        offenders.finish();
+    }
+
+    // quick smell test before the full color check: one color per artist
+    let distinct_colors = canvas
+        .iter()
+        .map(|p| p.color)
+        .collect::<HashSet<Color>>()
+        .len();
+    say!(
+        "{} distinct colors, {} distinct artists",
+        distinct_colors,
+        posns_map.len()
+    );
+    if distinct_colors != posns_map.len() {
+        warn!("the number of colors does not match the number of artists.");
+    }
 
-    // verify that a sufficient number of artists exist
-    // possible but highly unlikely this fails due to starvation, not a lack of generation
-    print_err_msg(check_enough_artists(
-        &posns_map.keys().collect::<Vec<_>>()[..],
-        54,
-    ));
+    if options.stats {
+        print_artist_stats(&posns_map);
+        print_first_and_last(&canvas);
+        print_paint_counts(&canvas);
+    }
 
-    // check that all artists draw at least one pixel
-    print_err_msg(check_all_artists_draw(&posns_map, 1));
+    if options.color_stats {
+        print_color_stats(&canvas, &colors_map);
+    }
 
-    // verify that all artists have a unique color
-    print_err_msg(check_colors_unique(&canvas));
+    if options.completion_order {
+        print_completion_order(&canvas);
+    }
 
-    // verify that no artists paint over one another
-    print_err_msg(check_no_overlapping(&posns_map));
+    if let Some(cell_size) = options.density {
+        print_density(&canvas, cell_size);
+    }
+
+    if options.quadrants {
+        let min_fraction = options.quadrant_min.unwrap_or(QUADRANT_MIN_FRACTION);
+        print_quadrants(
+            &canvas,
+            summary.bounds,
+            options.render_options.origin,
+            min_fraction,
+        );
+    }
 
-    // verify that there are no islands in the log file
-    // print_err_msg(check_no_islands(&posns_map));
+    if options.interleaving {
+        print_interleaving(&posns_map);
+    }
 
-    // double check for artists receiving the same random value. this can be done
-    // by analyzing their points, to see if two sets of points are isomorphic
-    // this is impossible if each thread has its own rng, but some patterns may not
-    // show up even if they have the same rng because of competing for pixels within the pattern
-    print_err_msg(check_no_repeating_patterns(posns_map));
+    if options.runs {
+        print_runs(&posns_map);
+    }
 
-    println!("Finished analyzing the log.");
+    if let Some(path) = &options.dot {
+        let mut file = File::create(path)?;
+        write_adjacency_dot(&canvas, &colors_map, &mut file)?;
+        say!("Wrote the artist adjacency graph to {}.", path);
+    }
 
-    Ok(())
-}
+    if let Some(path) = &options.dump_map {
+        let mut file = io::BufWriter::new(File::create(path)?);
+        write_posns_json(&posns_map, &mut file)?;
+        file.flush()?;
+        say!("Wrote each artist's points to {}.", path);
+    }
 
-fn check_enough_artists(artists: &[&Artist], num_artists: usize) -> Result<(), String> {
-    if artists.len() != num_artists {
-        Err(format!(
-            "Expected {} artists, but found {}; incorrect number of artists painted!",
-            num_artists,
-            artists.len()
-        ))
-    } else {
-        println!("Found {} artists!", num_artists);
-        Ok(())
+    if let Some(path) = &options.stats_csv {
+        let mut file = io::BufWriter::new(File::create(path)?);
+        write_stats_csv(&posns_map, &colors_map, &mut file)?;
+        file.flush()?;
+        say!("Wrote each artist's stats to {}.", path);
     }
-}
 
-fn check_all_artists_draw(
-    posns_map: &HashMap<Artist, HashSet<Point>>,
-    num_pixels: usize,
-) -> Result<(), String> {
-    println!(
-        "Verifying that all artists draw at least {} pixels...",
-        num_pixels
-    );
-    let mut draw_error = false;
-    for (artist, points) in posns_map.iter() {
-        if points.len() < num_pixels {
-            draw_error = true;
-            eprintln!(
-                "Artist {} drew {} pixels; should draw at least {} pixels.",
-                artist,
-                points.len(),
-                num_pixels
-            );
+    if let Some(path) = &options.heatmap {
+        let image = render_heatmap(&canvas, options.render_options.origin)?;
+        let mut file = io::BufWriter::new(File::create(path)?);
+        image.write_ppm(&mut file)?;
+        file.flush()?;
+        say!(
+            "Drew how many artists painted each cell to {} ({}x{}).",
+            path,
+            image.width,
+            image.height
+        );
+    }
+
+    if let Some(path) = &options.render {
+        let image = render(&canvas, &options.render_options)?;
+        let mut file = io::BufWriter::new(File::create(path)?);
+        image.write_ppm(&mut file)?;
+        file.flush()?;
+        say!(
+            "Drew the canvas to {} ({}x{}).",
+            path,
+            image.width,
+            image.height
+        );
+    }
+
+    // the protected background and the palette, read only when their checks run
+    let protected = match &options.protected {
+        Some(path) if results.wants("protected") => {
+            Some(read_points(BufReader::new(File::open(path)?))?)
         }
+        _ => None,
+    };
+    let palette = match (&options.palette_colors, &options.palette) {
+        (None, None) => None,
+        _ if !results.wants("palette") => None,
+        (Some(colors), _) => Some(colors.clone()),
+        (None, Some(path)) => Some(read_palette(BufReader::new(File::open(path)?))?),
+    };
+    let config = CheckConfig::builder()
+        .top_n(top_n)
+        .expect_pixels(options.expect_pixels)
+        .dedupe_pixels(options.dedupe_pixels)
+        .size(size)
+        .require_full(options.require_full)
+        .expect_aspect(options.expect_aspect)
+        .artist_count(options.artist_count)
+        .artist_ids(options.artist_ids.clone())
+        .id_base(options.id_base)
+        .tid_range(options.tid_range)
+        .min_pixels(options.min_pixels)
+        .max_pixels(options.max_pixels)
+        .require_color_per_artist(options.require_color_per_artist)
+        .check_cvd(options.check_cvd)
+        .color_metric(options.color_metric)
+        .color_from(options.color_from)
+        .check_grayscale(options.check_grayscale)
+        .timestamped(options.parse.with_timestamp)
+        .check_aliasing(options.check_aliasing)
+        .check_gaps(options.check_gaps)
+        .protected(protected)
+        .palette(palette)
+        .self_overlap(options.self_overlap)
+        .max_overlaps(options.max_overlaps)
+        .check_color_regions(options.check_color_regions)
+        .check_outliers(options.check_outliers)
+        .outlier_distance(options.outlier_distance)
+        .check_holes(options.check_holes)
+        .check_mirrors(options.check_mirrors)
+        .check_shared_rng(options.check_shared_rng)
+        .rng_run(options.rng_run)
+        .check_lattice(options.check_lattice)
+        .check_double_logged(options.check_double_logged)
+        .build();
+    let ctx = AnalysisContext {
+        canvas: &canvas,
+        posns_map: &posns_map,
+        colors_map: &colors_map,
+        bounds: summary.bounds,
+        config: &config,
+    };
+    Registry::builtin().run_all(&ctx, &mut results);
+
+    say!("Finished analyzing the log.");
+    if options.by_severity {
+        results.print_by_severity();
     }
-    if draw_error {
-        Err("Artists did not draw enough pixels: could be starved, but make sure that all artists have a chance to draw!".to_string())
-    } else {
-        println!("All artists draw at least {} pixels!", num_pixels);
-        Ok(())
+    results.print_summary();
+    results.print_table();
+
+    if options.format == ReportFormat::Ndjson {
+        let mut records: Vec<Record> = artist_reports(&posns_map, &colors_map)
+            .into_iter()
+            .map(Record::Artist)
+            .collect();
+        records.push(Record::Summary(run_summary(
+            &results,
+            posns_map.len(),
+            canvas.len(),
+        )));
+        let stdout = io::stdout();
+        write_ndjson(&records, &mut stdout.lock())?;
     }
+
+    if options.repl {
+        let stdin = io::stdin();
+        let stdout = io::stdout();
+        run_repl(
+            &canvas,
+            &posns_map,
+            &colors_map,
+            stdin.lock(),
+            &mut stdout.lock(),
+        )?;
+    }
+
+    Ok(run_summary(&results, posns_map.len(), canvas.len()))
 }
 
-fn check_colors_unique(canvas: &Canvas) -> Result<(), String> {
-    println!("Verifying that all artists use unique colors...");
-    let mut color_error = false;
-    let mut color_set: HashMap<Color, Artist> = HashMap::new();
-    for pixel in canvas {
-        if let Some(artist) = color_set.get(&pixel.color) {
-            if *artist == pixel.artist {
-                continue;
-            } else {
-                color_error = true;
-                eprintln!(
-                    "Artist {} uses color {}, which is also used by artist {}",
-                    pixel.artist,
-                    pixel.color,
-                    color_set.get(&pixel.color).unwrap()
-                );
+// writes each parse error to the error log if one was given, otherwise to stderr
+fn report_parse_errors(errors: &[ParseError], error_log: Option<&str>) -> io::Result<()> {
+    match error_log {
+        Some(path) => {
+            let mut file = File::create(path)?;
+            for error in errors {
+                writeln!(file, "{}", error)?;
+            }
+            eprintln!("Wrote {} parse errors to {}.", errors.len(), path);
+        }
+        None => {
+            for error in errors {
+                eprintln!("{}", error);
             }
-        } else {
-            color_set.insert(pixel.color, pixel.artist);
         }
     }
-    if color_error {
-        return Err(
-            "Ensure that each artist must have a unique color, when you generate artists!"
-                .to_string(),
-        );
+    Ok(())
+}
+
+// takes --include-seq out of the arguments of a subcommand that writes pixels
+// back out as a log, returning the rest and whether it was there
+fn take_include_seq(args: &[String]) -> (Vec<String>, bool) {
+    let rest: Vec<String> = args
+        .iter()
+        .filter(|arg| *arg != "--include-seq")
+        .cloned()
+        .collect();
+    let include_seq = rest.len() < args.len();
+    (rest, include_seq)
+}
+
+// writes pixels to stdout as a log, with a seq column under --include-seq
+fn write_pixels(canvas: &Canvas, include_seq: bool) -> io::Result<()> {
+    let stdout = io::stdout();
+    if include_seq {
+        write_log_with_seq(canvas, &mut stdout.lock())
     } else {
-        println!("All artists use unique colors!");
+        write_log(canvas, &mut stdout.lock())
     }
+}
 
+// crop <log> x0 y0 x1 y1 [--include-seq]
+// writes only the pixels inside the given (inclusive) box to stdout as a new log
+fn crop(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let (args, include_seq) = take_include_seq(args);
+    if args.len() != 5 {
+        return Err("Usage: crop <log> x0 y0 x1 y1 [--include-seq]".into());
+    }
+    let mut bounds = [0i16; 4];
+    for (bound, arg) in bounds.iter_mut().zip(args[1..].iter()) {
+        *bound = arg
+            .parse::<i16>()
+            .map_err(|_| format!("Invalid crop coordinate {}", arg))?;
+    }
+    let (x0, x1) = (min(bounds[0], bounds[2]), max(bounds[0], bounds[2]));
+    let (y0, y1) = (min(bounds[1], bounds[3]), max(bounds[1], bounds[3]));
+
+    let canvas = Canvas::from_file(&args[0])?;
+    let cropped: Canvas = canvas
+        .into_iter()
+        .filter(|p| (x0..=x1).contains(&p.coord.x) && (y0..=y1).contains(&p.coord.y))
+        .collect();
+    eprintln!("Kept {} pixels inside the crop region.", cropped.len());
+
+    write_pixels(&cropped, include_seq)?;
     Ok(())
 }
 
-fn check_no_overlapping(posns_map: &HashMap<Artist, HashSet<Point>>) -> Result<(), String> {
-    println!("Verifying that no artists paint over one another...");
-    let mut posn_error = false;
-    for (artist, posns) in posns_map.iter() {
-        for (other_artist, other_posns) in posns_map.iter() {
-            if artist == other_artist {
-                continue;
-            } else {
-                let intersect: Vec<&Point> = posns.intersection(other_posns).collect();
-                if intersect.len() > 0 {
-                    posn_error = true;
-                    eprintln!(
-                        "Artist {} overlaps with artist {} at the following points:",
-                        artist, other_artist
-                    );
-                    for point in intersect {
-                        eprintln!("{:#?}", point);
-                    }
-                    eprintln!("All errors for artist {} complete.", artist);
-                }
-            }
-        }
+// extract <log> <artist-id> [--include-seq]
+// writes only one artist's pixels to stdout as a new log, in their original order
+fn extract(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let (args, include_seq) = take_include_seq(args);
+    if args.len() != 2 {
+        return Err("Usage: extract <log> <artist-id> [--include-seq]".into());
     }
-    if posn_error {
-        return Err("Make sure that artists do not paint to the same position- you may need to lock the position or ensure artists skip the position if it is locked.".to_string());
-    } else {
-        println!("All artists paint on separate pixels!");
+    let artist = args[1]
+        .parse::<Artist>()
+        .map_err(|_| format!("Invalid artist id {}", args[1]))?;
+
+    let canvas = Canvas::from_file(&args[0])?;
+    let extracted: Canvas = canvas.into_iter().filter(|p| p.artist == artist).collect();
+    if extracted.is_empty() {
+        return Err(format!("Artist {} never painted", artist).into());
     }
+    eprintln!(
+        "Kept {} pixels painted by artist {}.",
+        extracted.len(),
+        artist
+    );
 
+    write_pixels(&extracted, include_seq)?;
     Ok(())
 }
 
-fn check_no_islands(posns_map: &HashMap<Artist, HashSet<Point>>) -> Result<(), String> {
-    println!("Verifying that all pixels are connected to pixels of the same color...");
-    unimplemented!()
-}
+// compare-artists <log> A B [--origin top-left|bottom-left]
+// draws both artists' patterns next to each other and names the rotation or
+// reflection that turns A's into B's, if there is one
+fn compare_artists(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let usage = "Usage: compare-artists <log> A B [--origin top-left|bottom-left] [--render <left.ppm> <right.ppm>]";
+    if args.len() < 3 {
+        return Err(usage.into());
+    }
+    let mut origin = Origin::TopLeft;
+    let mut render_to = None;
+    let mut flags = args[3..].iter();
+    while let Some(flag) = flags.next() {
+        match (flag.as_str(), flags.next()) {
+            ("--origin", Some(value)) => origin = value.parse::<Origin>()?,
+            ("--render", Some(left)) => match flags.next() {
+                Some(right) => render_to = Some((left, right)),
+                None => return Err(usage.into()),
+            },
+            _ => return Err(usage.into()),
+        }
+    }
+    let a = args[1]
+        .parse::<Artist>()
+        .map_err(|_| format!("Invalid artist id {}", args[1]))?;
+    let b = args[2]
+        .parse::<Artist>()
+        .map_err(|_| format!("Invalid artist id {}", args[2]))?;
 
-fn check_no_repeating_patterns(posns_map: HashMap<Artist, HashSet<Point>>) -> Result<(), String> {
-    println!("Checking for duplicated artist patterns...");
-    let normalized: Vec<(Artist, HashSet<Point>)> = posns_map
-        .into_iter()
-        .map(|(artist, set)| (artist, normalize_points(set).expect("Failed to normalize set: ")))
-        .collect();
+    let canvas = Canvas::from_file(&args[0])?;
+    let bounds = canvas
+        .bounds()
+        .unwrap_or(BoundingBox::new(Point::new(0, 0)));
+    // flip into top-left coordinates first, so the drawing and the names of
+    // the rotations and mirrors match how the canvas really looks
+    let mut points: HashMap<Artist, HashSet<Point>> = HashMap::new();
+    for pixel in &canvas {
+        if pixel.artist == a || pixel.artist == b {
+            points
+                .entry(pixel.artist)
+                .or_default()
+                .insert(origin.to_top_left(pixel.coord, &bounds));
+        }
+    }
+    let a_points = points
+        .get(&a)
+        .ok_or_else(|| format!("Artist {} never painted", a))?;
+    let b_points = points
+        .get(&b)
+        .ok_or_else(|| format!("Artist {} never painted", b))?;
 
-    let mut duplicates: HashSet<(Artist, Artist)> = HashSet::new();
-    let mut count = 0;
-    for (artist, set) in normalized.iter() {
-        for (other_artist, other_set) in normalized.iter() {
-            if artist == other_artist {
-                continue;
-            } else if set.is_subset(other_set) && other_set.is_superset(set) {
-                count += 1;
-                let is_new = duplicates.insert((min(*artist, *other_artist), max(*artist, *other_artist)));
-                if is_new {
-                    eprintln!("Duplicate pattern found with artists {} and {}! So far, found {} duplicates", artist, other_artist, duplicates.len());
-                }
+    let (transform, a_shape, b_shape) = best_alignment(a_points, b_points);
+    let differing = a_shape.symmetric_difference(&b_shape).count();
+    if differing == 0 {
+        println!("Artist {}'s pattern is artist {}'s {}.", b, a, transform);
+    } else {
+        println!(
+            "No rotation or reflection of artist {}'s pattern matches artist {}'s; the closest is {}, with {} differing cells marked x.",
+            a, b, transform, differing
+        );
+    }
+    println!(
+        "Left: artist {} ({} pixels), {}. Right: artist {} ({} pixels).",
+        a,
+        a_points.len(),
+        transform,
+        b,
+        b_points.len()
+    );
+    match side_by_side(&a_shape, &b_shape) {
+        Ok(rows) => {
+            for row in rows {
+                println!("{}", row);
             }
         }
+        Err(msg) => println!("{}.", msg),
     }
 
-    if duplicates.is_empty() {
-        println!("No duplicate patterns found!");
-        Ok(())
-    } else {
-        Err(format!("Found {} duplicate patterns: {} adds", duplicates.len(), count))
+    if let Some((left, right)) = render_to {
+        // each pattern in the first color its artist painted with
+        let color_of = |artist: Artist| {
+            canvas
+                .iter()
+                .find(|p| p.artist == artist)
+                .map(|p| p.color)
+                .unwrap_or(BACKGROUND)
+        };
+        let (a_image, b_image) = render_aligned((&a_shape, color_of(a)), (&b_shape, color_of(b)))?;
+        for (path, image) in [(left, a_image), (right, b_image)] {
+            let mut file = io::BufWriter::new(File::create(path)?);
+            image.write_ppm(&mut file)?;
+            file.flush()?;
+        }
+        println!(
+            "Drew artist {}'s pattern, {}, to {} and artist {}'s to {}.",
+            a, transform, left, b, right
+        );
     }
+    Ok(())
 }
 
-fn normalize_points(points: HashSet<Point>) -> Result<HashSet<Point>, String> {
-    // find the rightmost point for the relative "origin"
-    let rightmost = match points.iter().max() {
-        Some(point) => point,
-        None => {
-            return Err("Error finding rightmost point in the set of points.".to_string());
-        }
-    };
+// validate <log> <expectations.json>
+// runs exactly the checks the expectations call for, with the human-readable
+// report on stderr, then writes which expectations were unmet to stdout as JSON
+fn validate(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let usage = "Usage: validate <log> <expectations.json> [--json-pretty|--json-compact]";
+    match args.get(2).map(String::as_str) {
+        _ if args.len() < 2 || args.len() > 3 => return Err(usage.into()),
+        None => (),
+        Some("--json-pretty") => set_json_pretty(Some(true)),
+        Some("--json-compact") => set_json_pretty(Some(false)),
+        Some(_) => return Err(usage.into()),
+    }
+    let expectations = Expectations::from_reader(BufReader::new(File::open(&args[1])?))?;
 
-    Ok(points
+    let mut options = Options {
+        artist_count: expectations.artists,
+        min_pixels: expectations.min_pixels,
+        max_pixels: expectations.max_pixels,
+        palette_colors: expectations.palette_colors(),
+        width: expectations.width,
+        height: expectations.height,
+        require_full: expectations.width.is_some(),
+        ..Options::default()
+    };
+    let mut only: HashSet<&'static str> = expectations
+        .judged_by()
         .iter()
-        .map(|p| Point::new(p.x - rightmost.x, p.y - rightmost.y))
-        .collect::<HashSet<Point>>())
+        .map(|(_, check)| *check)
+        .collect();
+    if let Some(checks) = &expectations.checks {
+        only.extend(parse_check_names(&checks.join(","), "the expectations")?);
+    }
+    for check in &only {
+        enable_check(&mut options, check)?;
+    }
+    options.only = Some(only);
+
+    set_human_to_stderr(true);
+    let summary = analyze(&options, &args[0])?;
+    let validation = Validation::new(&expectations, summary);
+    write_validation(&validation, &mut io::stdout().lock())?;
+    if !validation.passed {
+        std::process::exit(1);
+    }
+    Ok(())
 }
 
-// format of lines:
-// artist_tid, x, y, r, g, b\n
-fn read_log_to_canvas(lines: Vec<String>) -> Result<Canvas, Box<dyn Error>> {
-    let mut pixels: Vec<CanvasPixel> = Vec::new();
-    for (lnum, line) in lines.iter().enumerate() {
-        let parts: Vec<&str> = line.split(' ').map(|s| s.trim_end_matches(',')).collect();
-        if parts.len() != 6 {
-            Err("Line is formatted improperly".to_string())?
+// turns on the check for validate, or fails if it needs settings the
+// expectations don't have
+fn enable_check(options: &mut Options, check: &str) -> Result<(), String> {
+    match check {
+        "cvd" => options.check_cvd = true,
+        "grayscale" => options.check_grayscale = true,
+        "color-per-artist" => options.require_color_per_artist = true,
+        "color-regions" => options.check_color_regions = true,
+        "holes" => options.check_holes = true,
+        "mirrors" => options.check_mirrors = true,
+        "lattice" => options.check_lattice = true,
+        "double-logged" => options.check_double_logged = true,
+        "outliers" => options.check_outliers = true,
+        "shared-rng" => options.check_shared_rng = true,
+        "self-overlap" => options.self_overlap = SelfOverlap::Error,
+        // on by default
+        "artist-count" | "all-draw" | "colors" | "overlap" | "coincident" | "patterns" => (),
+        "full" | "border" if options.width.is_some() => (),
+        "max-pixels" if options.max_pixels.is_some() => (),
+        "palette" if options.palette_colors.is_some() => (),
+        _ => {
+            return Err(format!(
+                "The {} check needs settings the expectations can't give; run it from the command line instead",
+                check
+            ))
         }
-        let artist_tid = try_parse::<Artist>(parts[0], "artist", lnum)?;
-        let x_pos = try_parse::<i16>(parts[1], "x", lnum)?;
-        let y_pos = try_parse::<i16>(parts[2], "y", lnum)?;
-        let red = try_parse::<u8>(parts[3], "red", lnum)?;
-        let green = try_parse::<u8>(parts[4], "green", lnum)?;
-        let blue = try_parse::<u8>(parts[5], "blue", lnum)?;
+    }
+    Ok(())
+}
 
-        pixels.push(CanvasPixel::new(artist_tid, x_pos, y_pos, red, green, blue));
+// canonicalize <log> [--by seq|artist] [--include-seq]
+// rewrites the log to stdout in one consistent format, in paint order or
+// sorted by (artist, y, x), so logs with the same pixels diff cleanly
+fn canonicalize(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let usage = "Usage: canonicalize <log> [--by seq|artist] [--include-seq]";
+    let (args, include_seq) = take_include_seq(args);
+    let by_artist = match (args.len(), args.get(1).map(String::as_str)) {
+        (1, None) => false,
+        (3, Some("--by")) => match args[2].as_str() {
+            "seq" => false,
+            "artist" => true,
+            other => {
+                return Err(format!("Unknown sort key {}; expected seq or artist", other).into())
+            }
+        },
+        _ => return Err(usage.into()),
+    };
+
+    let canvas = Canvas::from_file(&args[0])?;
+    let mut pixels = canvas.pixels().to_vec();
+    if by_artist {
+        pixels.sort_by_key(|p| (p.artist, p.coord.y, p.coord.x, p.seq));
+    } else {
+        pixels.sort_by_key(|p| p.seq);
     }
 
-    println!("{} pixels were painted", pixels.len());
-    Ok(pixels)
+    write_pixels(&Canvas::new(pixels), include_seq)?;
+    Ok(())
 }
 
-fn try_parse<T: FromStr>(s: &str, name: &str, line_num: usize) -> Result<T, <T as FromStr>::Err> {
-    match s.parse::<T>() {
-        Ok(val) => Ok(val),
-        Err(e) => {
-            eprintln!(
-                "Failed to parse {} on line {}; formatted incorrectly",
-                name, line_num
-            );
-            Err(e)
-        }
+// save-bin <log> <out> [--with-timestamp]
+// parses a text log once and saves it in the binary format, which the
+// analysis and load-bin read back without parsing text
+fn save_bin(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let usage = "Usage: save-bin <log> <out> [--with-timestamp]";
+    let options = match args.get(2).map(String::as_str) {
+        None if args.len() == 2 => ParseOptions::default(),
+        Some("--with-timestamp") if args.len() == 3 => ParseOptions {
+            with_timestamp: true,
+            ..ParseOptions::default()
+        },
+        _ => return Err(usage.into()),
+    };
+    let (canvas, errors) = read_log_to_canvas(BufReader::new(File::open(&args[0])?), &options)?;
+    if let Some(error) = errors.into_iter().next() {
+        return Err(error.into());
     }
+    let mut out = io::BufWriter::new(File::create(&args[1])?);
+    write_binary(&canvas, &mut out)?;
+    out.flush()?;
+    eprintln!("Saved {} pixels to {}.", canvas.len(), args[1]);
+    Ok(())
 }
 
-fn print_err_msg<T, E: fmt::Display>(res: Result<T, E>) {
-    if let Err(msg) = res {
-        eprintln!("{}", msg);
+// load-bin <file> [--include-seq]
+// writes a binary canvas back out to stdout as a text log
+fn load_bin(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let (args, include_seq) = take_include_seq(args);
+    if args.len() != 1 {
+        return Err("Usage: load-bin <file> [--include-seq]".into());
     }
+    let canvas = read_binary(&mut BufReader::new(File::open(&args[0])?))?;
+    write_pixels(&canvas, include_seq)?;
+    Ok(())
 }
@@ -0,0 +1,836 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Read, Write};
+use std::ops::Range;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::canvas::{Artist, BoundingBox, Canvas, CanvasPixel, Point};
+use crate::color::Color;
+
+/// How long a line may be by default before the reader rejects it: 1 MiB.
+pub const DEFAULT_MAX_LINE_LENGTH: usize = 1 << 20;
+
+/// How log lines should be interpreted.
+#[derive(Debug, Copy, Clone)]
+pub struct ParseOptions {
+    /// Skip malformed lines instead of stopping at the first one.
+    pub lenient: bool,
+    pub line_format: LineFormat,
+    pub color_format: ColorFormat,
+    /// Lines start with a timestamp column: `timestamp artist_tid, x, y, r, g, b`.
+    pub with_timestamp: bool,
+    /// Only parse the lines in this range; the rest of the log is ignored.
+    pub lines: Option<LineRange>,
+    /// Fill color fields missing from the end of a line with 0. The artist
+    /// and coordinates (and timestamp, if any) must still be there.
+    pub default_missing: bool,
+    /// Lines longer than this many bytes are rejected without reading the
+    /// rest of them into memory.
+    pub max_line_length: usize,
+    /// Stop with an error instead of loading more than this many pixels, so
+    /// an enormous log can't use up all the memory. `None` for no limit.
+    pub pixel_budget: Option<usize>,
+    /// Lines may end with a `LOCKED` or `SKIPPED` token, for paints an artist
+    /// gave up on because the position was locked. Those lines are counted
+    /// as skipped paints instead of pixels.
+    pub lock_markers: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            lenient: false,
+            line_format: LineFormat::default(),
+            color_format: ColorFormat::default(),
+            with_timestamp: false,
+            lines: None,
+            default_missing: false,
+            max_line_length: DEFAULT_MAX_LINE_LENGTH,
+            pixel_budget: None,
+            lock_markers: false,
+        }
+    }
+}
+
+impl ParseOptions {
+    // how many space separated fields a full line has
+    fn field_count(&self) -> usize {
+        let timestamp = if self.with_timestamp { 1 } else { 0 };
+        timestamp + 3 + self.color_field_count()
+    }
+
+    // how many of those fields hold the color
+    fn color_field_count(&self) -> usize {
+        if self.color_format == ColorFormat::Packed {
+            1
+        } else {
+            3
+        }
+    }
+}
+
+/// A range of 1-based line numbers, written `start..end` (end excluded) or
+/// `start..=end` (end included); either side may be left off. Ranges that
+/// select no lines, like `10..5`, are rejected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LineRange {
+    pub start: usize,
+    /// The first line past the range, or `None` to read to the end.
+    pub end: Option<usize>,
+}
+
+impl LineRange {
+    pub fn contains(&self, line_num: usize) -> bool {
+        line_num >= self.start && self.end.is_none_or(|end| line_num < end)
+    }
+}
+
+impl FromStr for LineRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid line range {}; expected e.g. 1000..2000", s);
+        let (start, end) = s.split_once("..").ok_or_else(invalid)?;
+        let (end, inclusive) = match end.strip_prefix('=') {
+            Some(end) => (end, true),
+            None => (end, false),
+        };
+        let start = match start {
+            "" => 1,
+            start => start.parse::<usize>().map_err(|_| invalid())?.max(1),
+        };
+        let end = match end {
+            "" if !inclusive => None,
+            end => {
+                let end = end.parse::<usize>().map_err(|_| invalid())?;
+                // no line comes after the last line number there is, so
+                // including it reads to the end
+                if inclusive {
+                    end.checked_add(1)
+                } else {
+                    Some(end)
+                }
+            }
+        };
+        if end.is_some_and(|end| end <= start) {
+            return Err(format!(
+                "Line range {} selects no lines; it must start before it ends",
+                s
+            ));
+        }
+        Ok(LineRange { start, end })
+    }
+}
+
+impl fmt::Display for LineRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.end {
+            Some(end) => write!(f, "{}..{}", self.start, end),
+            None => write!(f, "{}..", self.start),
+        }
+    }
+}
+
+/// How each line of a log is laid out.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum LineFormat {
+    /// Comma separated fields, `artist_tid, x, y, r, g, b`. The default.
+    #[default]
+    Text,
+    /// One JSON object per line, `{"artist":1,"x":2,"y":3,"r":4,"g":5,"b":6}`,
+    /// with a `timestamp` as well for timestamped logs. Color formats, lock
+    /// markers, and missing-field defaults only apply to text.
+    Jsonl,
+}
+
+impl FromStr for LineFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(LineFormat::Text),
+            "jsonl" => Ok(LineFormat::Jsonl),
+            _ => Err(format!(
+                "Unknown input format {}; expected text or jsonl",
+                s
+            )),
+        }
+    }
+}
+
+/// How the r, g, b fields of a line are written.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum ColorFormat {
+    /// Integers from 0 to 255.
+    #[default]
+    Int,
+    /// Normalized floats from 0.0 to 1.0.
+    Float,
+    /// Floats if any color field on the line has a decimal point, integers otherwise.
+    Auto,
+    /// One decimal integer holding `0xRRGGBB`, in place of the three fields.
+    Packed,
+}
+
+impl FromStr for ColorFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "int" => Ok(ColorFormat::Int),
+            "float" => Ok(ColorFormat::Float),
+            "auto" => Ok(ColorFormat::Auto),
+            "packed" => Ok(ColorFormat::Packed),
+            _ => Err(format!(
+                "Unknown color format {}; expected int, float, auto, or packed",
+                s
+            )),
+        }
+    }
+}
+
+/// Why one log line could not be parsed, down to the field at fault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineError {
+    /// The line had the wrong number of space separated fields.
+    FieldCount { expected: usize, found: usize },
+    /// A field didn't hold the type it should.
+    Field {
+        /// `timestamp`, `artist`, `x`, `y`, `red`, `green`, `blue`, or
+        /// `color` for a packed color.
+        name: &'static str,
+        /// What the field should hold, e.g. `an integer from 0 to 255`.
+        expected: &'static str,
+        /// The field as written, without its trailing comma.
+        value: String,
+        /// Where `value` sits in the line, in bytes.
+        span: Range<usize>,
+    },
+    /// The line ran past the longest line the reader allows, in bytes.
+    TooLong { limit: usize },
+    /// The line isn't text: its bytes stop being valid UTF-8 at `valid_up_to`.
+    NotUtf8 { valid_up_to: usize },
+    /// A JSONL line didn't hold a pixel object.
+    Json {
+        /// What serde made of it, e.g. ``missing field `x` ``.
+        message: String,
+        /// The 1-based column it gave up at.
+        column: usize,
+    },
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LineError::FieldCount { expected, found } => write!(
+                f,
+                "Line is formatted improperly; expected {} fields, found {}",
+                expected, found
+            ),
+            LineError::Field {
+                name,
+                expected,
+                value,
+                ..
+            } => write!(
+                f,
+                "Failed to parse {} from {:?}; expected {}",
+                name, value, expected
+            ),
+            LineError::TooLong { limit } => write!(
+                f,
+                "Line is longer than the {} byte limit; the log may be corrupted",
+                limit
+            ),
+            LineError::NotUtf8 { valid_up_to } => write!(
+                f,
+                "Line isn't valid UTF-8 past byte {}; the log may be corrupted",
+                valid_up_to
+            ),
+            LineError::Json { message, column } => {
+                write!(f, "Invalid JSON pixel at column {}: {}", column, message)
+            }
+        }
+    }
+}
+
+impl Error for LineError {}
+
+/// A log line that could not be turned into a pixel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line_num: usize,
+    pub line: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Line {}: {}: {:?}",
+            self.line_num, self.reason, self.line
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// Anything that can go wrong turning a log into a `Canvas`.
+#[derive(Debug)]
+pub enum LogError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for LogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogError::Io(e) => write!(f, "Error reading log: {}", e),
+            LogError::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for LogError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LogError::Io(e) => Some(e),
+            LogError::Parse(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for LogError {
+    fn from(e: io::Error) -> LogError {
+        LogError::Io(e)
+    }
+}
+
+impl From<ParseError> for LogError {
+    fn from(e: ParseError) -> LogError {
+        LogError::Parse(e)
+    }
+}
+
+/// What the parser saw while reading a log, gathered as it streamed through.
+#[derive(Debug, Default)]
+pub struct ParseSummary {
+    /// The box containing every parsed pixel, or `None` if there were none.
+    pub bounds: Option<BoundingBox>,
+    /// The lines that failed to parse.
+    pub errors: Vec<ParseError>,
+    /// Lines that were missing color fields and had them filled with 0.
+    pub defaulted: usize,
+    /// How the log's last line looks cut off, when it does.
+    pub truncated: Option<Truncation>,
+    /// Paints that were logged with a lock marker instead of painted, in
+    /// log order.
+    pub skipped: Vec<SkippedPaint>,
+}
+
+/// The trailing token on a line for a paint that didn't happen.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LockMarker {
+    /// `LOCKED`: the position was locked by another artist.
+    Locked,
+    /// `SKIPPED`: the artist moved on without painting.
+    Skipped,
+}
+
+impl fmt::Display for LockMarker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LockMarker::Locked => f.write_str("LOCKED"),
+            LockMarker::Skipped => f.write_str("SKIPPED"),
+        }
+    }
+}
+
+/// A paint an artist logged but gave up on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SkippedPaint {
+    pub line_num: usize,
+    pub artist: Artist,
+    pub coord: Point,
+    pub marker: LockMarker,
+}
+
+/// Signs that a log's last line was only partly written, as when the
+/// simulation crashed mid-write. Both carry the 1-based line number.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Truncation {
+    /// The last line parsed, but the file doesn't end with a newline.
+    NoTrailingNewline(usize),
+    /// The last line stops before all its fields were written.
+    IncompleteLine(usize),
+}
+
+impl fmt::Display for Truncation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Truncation::NoTrailingNewline(line_num) => write!(
+                f,
+                "the log doesn't end with a newline after line {}",
+                line_num
+            ),
+            Truncation::IncompleteLine(line_num) => {
+                write!(f, "the last line, line {}, is missing fields", line_num)
+            }
+        }
+    }
+}
+
+/// Reads a log line by line, where each line is formatted `artist_tid, x, y, r, g, b`.
+/// Stops at the first malformed line unless lenient, in which case malformed
+/// lines are skipped; either way, the lines that failed are returned alongside
+/// the canvas.
+pub fn read_log_to_canvas(
+    reader: impl BufRead,
+    options: &ParseOptions,
+) -> io::Result<(Canvas, Vec<ParseError>)> {
+    let (canvas, summary) = read_log_with_summary(reader, options)?;
+    Ok((canvas, summary.errors))
+}
+
+/// Like `read_log_to_canvas`, but also returns the canvas bounds, worked out
+/// while parsing so nothing needs a second pass over the pixels. Fails with
+/// `ErrorKind::OutOfMemory` once the log holds more pixels than the options'
+/// `pixel_budget`.
+pub fn read_log_with_summary(
+    mut reader: impl BufRead,
+    options: &ParseOptions,
+) -> io::Result<(Canvas, ParseSummary)> {
+    let mut pixels: Vec<CanvasPixel> = Vec::new();
+    let mut summary = ParseSummary::default();
+    let mut buf: Vec<u8> = Vec::new();
+    let mut line_num = 0;
+    loop {
+        buf.clear();
+        // reading one byte past the limit (and its newline) is enough to
+        // know the line is too long. bytes rather than text, since the limit
+        // can cut a character in half
+        let limit = options.max_line_length;
+        let take = (limit as u64).saturating_add(2);
+        if reader.by_ref().take(take).read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+        // a line ends early only at the end of the file
+        let complete = buf.ends_with(b"\n");
+        let mut end = buf.len() - complete as usize;
+        while end > 0 && buf[end - 1] == b'\r' {
+            end -= 1;
+        }
+        let bytes = &buf[..end];
+        line_num += 1;
+        let too_long = bytes.len() > limit;
+        if too_long && !complete {
+            // drop the rest of the line without keeping it
+            reader.skip_until(b'\n')?;
+        }
+        let text = std::str::from_utf8(bytes);
+        let line = String::from_utf8_lossy(bytes);
+        let line = if too_long {
+            let mut end = 80.min(line.len());
+            while !line.is_char_boundary(end) {
+                end -= 1;
+            }
+            format!("{}...", &line[..end])
+        } else {
+            line.to_string()
+        };
+        if let Some(range) = options.lines {
+            if range.end.is_some_and(|end| line_num >= end) {
+                break;
+            }
+            if !range.contains(line_num) {
+                continue;
+            }
+        }
+        let parsed = match text {
+            _ if too_long => Err(LineError::TooLong { limit }),
+            Ok(text) => parse_marked_line(text, options),
+            Err(e) => Err(LineError::NotUtf8 {
+                valid_up_to: e.valid_up_to(),
+            }),
+        };
+        let at_end = reader.fill_buf()?.is_empty();
+        if at_end {
+            summary.truncated = match &parsed {
+                Err(LineError::FieldCount { expected, found }) if found < expected => {
+                    Some(Truncation::IncompleteLine(line_num))
+                }
+                Ok(_) if !complete => Some(Truncation::NoTrailingNewline(line_num)),
+                _ => None,
+            };
+        }
+        match parsed {
+            Ok((pixel, Some(marker))) => summary.skipped.push(SkippedPaint {
+                line_num,
+                artist: pixel.artist,
+                coord: pixel.coord,
+                marker,
+            }),
+            Ok((mut pixel, None)) => {
+                if options
+                    .pixel_budget
+                    .is_some_and(|budget| pixels.len() >= budget)
+                {
+                    return Err(io::Error::new(
+                        io::ErrorKind::OutOfMemory,
+                        format!(
+                            "stopped at line {} after loading {} pixels, the most the pixel budget allows",
+                            line_num,
+                            pixels.len()
+                        ),
+                    ));
+                }
+                if options.default_missing
+                    && options.line_format == LineFormat::Text
+                    && line.split(' ').count() < options.field_count()
+                {
+                    summary.defaulted += 1;
+                }
+                pixel.seq = pixels.len();
+                match &mut summary.bounds {
+                    Some(bounds) => bounds.include(pixel.coord),
+                    None => summary.bounds = Some(BoundingBox::new(pixel.coord)),
+                }
+                pixels.push(pixel);
+            }
+            Err(error) => {
+                summary.errors.push(ParseError {
+                    line_num,
+                    line,
+                    reason: error.to_string(),
+                });
+                if !options.lenient {
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok((Canvas::new(pixels), summary))
+}
+
+/// Parses one log line, `artist_tid, x, y, r, g, b`, with the default options.
+///
+/// ```
+/// use log_reader::{parse_line, LineError};
+///
+/// match parse_line("7, 1, 2, 255, 300, 0") {
+///     Err(LineError::Field { name, span, .. }) => {
+///         assert_eq!(name, "green");
+///         assert_eq!(span, 14..17);
+///     }
+///     other => panic!("unexpected {:?}", other),
+/// }
+/// ```
+pub fn parse_line(line: &str) -> Result<CanvasPixel, LineError> {
+    parse_line_with(line, &ParseOptions::default())
+}
+
+/// Parses one log line as `read_log_to_canvas` would with `options`. The
+/// pixel's `seq` is left at 0, since a lone line has no place in a log.
+///
+/// ```
+/// use log_reader::{parse_line_with, Color, ColorFormat, LineError, ParseOptions};
+///
+/// let options = ParseOptions {
+///     color_format: ColorFormat::Packed,
+///     ..ParseOptions::default()
+/// };
+/// let black = parse_line_with("7, 1, 2, 0", &options).unwrap();
+/// assert_eq!(black.color, Color::new(0, 0, 0));
+/// let white = parse_line_with("7, 1, 2, 16777215", &options).unwrap();
+/// assert_eq!(white.color, Color::new(255, 255, 255));
+/// assert!(matches!(
+///     parse_line_with("7, 1, 2, 16777216", &options),
+///     Err(LineError::Field { name: "color", .. })
+/// ));
+/// ```
+pub fn parse_line_with(line: &str, options: &ParseOptions) -> Result<CanvasPixel, LineError> {
+    parse_marked_line(line, options).map(|(pixel, _)| pixel)
+}
+
+/// Like `parse_line_with`, but also gives the line's lock marker when the
+/// options allow them, so a paint that was skipped can be told from one that
+/// happened.
+///
+/// ```
+/// use log_reader::{parse_marked_line, LockMarker, ParseOptions};
+///
+/// let options = ParseOptions {
+///     lock_markers: true,
+///     ..ParseOptions::default()
+/// };
+/// let (_, marker) = parse_marked_line("7, 1, 2, 255, 0, 0 LOCKED", &options).unwrap();
+/// assert_eq!(marker, Some(LockMarker::Locked));
+/// let (_, marker) = parse_marked_line("7, 1, 2, 255, 0, 0", &options).unwrap();
+/// assert_eq!(marker, None);
+/// ```
+pub fn parse_marked_line(
+    line: &str,
+    options: &ParseOptions,
+) -> Result<(CanvasPixel, Option<LockMarker>), LineError> {
+    if options.line_format == LineFormat::Jsonl {
+        return parse_json_line(line, options).map(|pixel| (pixel, None));
+    }
+    // each field without its trailing comma, and where it sits in the line
+    let mut fields: Vec<(&str, Range<usize>)> = Vec::new();
+    let mut start = 0;
+    for part in line.split(' ') {
+        let value = part.trim_end_matches(',');
+        fields.push((value, start..start + value.len()));
+        start += part.len() + 1;
+    }
+    let marker = match fields.last().map(|(value, _)| *value) {
+        Some("LOCKED") if options.lock_markers => Some(LockMarker::Locked),
+        Some("SKIPPED") if options.lock_markers => Some(LockMarker::Skipped),
+        _ => None,
+    };
+    if marker.is_some() {
+        fields.pop();
+    }
+    let expected = options.field_count();
+    // everything but the color fields has to be there to default them
+    if options.default_missing
+        && fields.len() < expected
+        && fields.len() >= expected - options.color_field_count()
+    {
+        while fields.len() < expected {
+            fields.push(("0", line.len()..line.len()));
+        }
+    }
+    if fields.len() != expected {
+        return Err(LineError::FieldCount {
+            expected,
+            found: fields.len(),
+        });
+    }
+    let timestamp = if options.with_timestamp {
+        Some(parse_field::<u64>(
+            &fields.remove(0),
+            "timestamp",
+            "an unsigned integer",
+        )?)
+    } else {
+        None
+    };
+    let artist_tid = parse_field::<Artist>(&fields[0], "artist", "an unsigned integer")?;
+    let x_pos = parse_field::<i16>(&fields[1], "x", "an integer from -32768 to 32767")?;
+    let y_pos = parse_field::<i16>(&fields[2], "y", "an integer from -32768 to 32767")?;
+    let color = if options.color_format == ColorFormat::Packed {
+        parse_packed(&fields[3])?
+    } else {
+        let floats = match options.color_format {
+            ColorFormat::Float => true,
+            ColorFormat::Auto => fields[3..6].iter().any(|(value, _)| value.contains('.')),
+            _ => false,
+        };
+        Color::new(
+            parse_channel(&fields[3], "red", floats)?,
+            parse_channel(&fields[4], "green", floats)?,
+            parse_channel(&fields[5], "blue", floats)?,
+        )
+    };
+
+    let mut pixel = CanvasPixel::new(artist_tid, x_pos, y_pos, color.r, color.g, color.b);
+    pixel.timestamp = timestamp;
+    Ok((pixel, marker))
+}
+
+// one line of a JSONL log
+#[derive(Deserialize)]
+struct JsonPixel {
+    artist: Artist,
+    x: i16,
+    y: i16,
+    r: u8,
+    g: u8,
+    b: u8,
+    timestamp: Option<u64>,
+}
+
+// a JSONL line, which needs a timestamp when the options say lines have one.
+// like a text line's, the timestamp is only kept then, so a log gives the
+// same pixels in either format
+fn parse_json_line(line: &str, options: &ParseOptions) -> Result<CanvasPixel, LineError> {
+    let json: JsonPixel = serde_json::from_str(line).map_err(|e| {
+        // the column is kept apart; every line of a JSONL log is line 1 to serde
+        let message = e.to_string();
+        let position = format!(" at line {} column {}", e.line(), e.column());
+        LineError::Json {
+            message: message
+                .strip_suffix(&position)
+                .unwrap_or(&message)
+                .to_string(),
+            column: e.column(),
+        }
+    })?;
+    if options.with_timestamp && json.timestamp.is_none() {
+        return Err(LineError::Json {
+            message: "missing field `timestamp`".to_string(),
+            column: line.len(),
+        });
+    }
+    let mut pixel = CanvasPixel::new(json.artist, json.x, json.y, json.r, json.g, json.b);
+    if options.with_timestamp {
+        pixel.timestamp = json.timestamp;
+    }
+    Ok(pixel)
+}
+
+/// Reads a set of coordinates, one `x y` pair per line. Blank lines are skipped.
+pub fn read_points(reader: impl BufRead) -> Result<HashSet<Point>, LogError> {
+    read_set(reader, parse_point)
+}
+
+/// Reads a palette of colors, one `r g b` triple per line. Blank lines are skipped.
+pub fn read_palette(reader: impl BufRead) -> Result<HashSet<Color>, LogError> {
+    read_set(reader, parse_color)
+}
+
+// one value per non-blank line, failing on the first line that doesn't parse
+fn read_set<T: Eq + std::hash::Hash>(
+    reader: impl BufRead,
+    parse: fn(&str) -> Result<T, String>,
+) -> Result<HashSet<T>, LogError> {
+    let mut values = HashSet::new();
+    for (lnum, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value = parse(&line).map_err(|reason| ParseError {
+            line_num: lnum + 1,
+            line: line.clone(),
+            reason,
+        })?;
+        values.insert(value);
+    }
+    Ok(values)
+}
+
+fn parse_color(line: &str) -> Result<Color, String> {
+    let parts: Vec<&str> = line
+        .split_whitespace()
+        .map(|s| s.trim_end_matches(','))
+        .collect();
+    if parts.len() != 3 {
+        return Err(format!(
+            "Line is formatted improperly; expected 3 fields, found {}",
+            parts.len()
+        ));
+    }
+    let red = try_parse::<u8>(parts[0], "red")?;
+    let green = try_parse::<u8>(parts[1], "green")?;
+    let blue = try_parse::<u8>(parts[2], "blue")?;
+    Ok(Color::new(red, green, blue))
+}
+
+fn parse_point(line: &str) -> Result<Point, String> {
+    let parts: Vec<&str> = line
+        .split_whitespace()
+        .map(|s| s.trim_end_matches(','))
+        .collect();
+    if parts.len() != 2 {
+        return Err(format!(
+            "Line is formatted improperly; expected 2 fields, found {}",
+            parts.len()
+        ));
+    }
+    let x = try_parse::<i16>(parts[0], "x")?;
+    let y = try_parse::<i16>(parts[1], "y")?;
+    Ok(Point::new(x, y))
+}
+
+fn try_parse<T: FromStr>(s: &str, name: &str) -> Result<T, String> {
+    s.parse::<T>().map_err(|_| {
+        format!(
+            "Failed to parse {} from {:?}; formatted incorrectly",
+            name, s
+        )
+    })
+}
+
+fn parse_field<T: FromStr>(
+    (value, span): &(&str, Range<usize>),
+    name: &'static str,
+    expected: &'static str,
+) -> Result<T, LineError> {
+    value.parse::<T>().map_err(|_| LineError::Field {
+        name,
+        expected,
+        value: value.to_string(),
+        span: span.clone(),
+    })
+}
+
+fn parse_channel(
+    field: &(&str, Range<usize>),
+    name: &'static str,
+    float: bool,
+) -> Result<u8, LineError> {
+    if !float {
+        return parse_field::<u8>(field, name, "an integer from 0 to 255");
+    }
+
+    let expected = "a float from 0.0 to 1.0";
+    let value = parse_field::<f32>(field, name, expected)?;
+    if !(0.0..=1.0).contains(&value) {
+        return Err(LineError::Field {
+            name,
+            expected,
+            value: field.0.to_string(),
+            span: field.1.clone(),
+        });
+    }
+    Ok((value * 255.0).round() as u8)
+}
+
+fn parse_packed(field: &(&str, Range<usize>)) -> Result<Color, LineError> {
+    let expected = "an integer from 0 to 16777215";
+    let value = parse_field::<u32>(field, "color", expected)?;
+    Color::from_packed(value).ok_or_else(|| LineError::Field {
+        name: "color",
+        expected,
+        value: field.0.to_string(),
+        span: field.1.clone(),
+    })
+}
+
+/// Writes the canvas back out in the log format `read_log_to_canvas` accepts,
+/// with a leading timestamp column on pixels that have one.
+pub fn write_log(canvas: &Canvas, out: &mut impl Write) -> io::Result<()> {
+    write_rows(canvas, out, false)
+}
+
+/// Writes the canvas like `write_log`, with each pixel's `seq` added as a
+/// last column: `artist_tid, x, y, r, g, b, seq`. The column is for working
+/// out paint order downstream; `read_log_to_canvas` doesn't read it back.
+pub fn write_log_with_seq(canvas: &Canvas, out: &mut impl Write) -> io::Result<()> {
+    write_rows(canvas, out, true)
+}
+
+fn write_rows(canvas: &Canvas, out: &mut impl Write, include_seq: bool) -> io::Result<()> {
+    for pixel in canvas {
+        if let Some(timestamp) = pixel.timestamp {
+            write!(out, "{} ", timestamp)?;
+        }
+        write!(
+            out,
+            "{}, {}, {}, {}, {}, {}",
+            pixel.artist, pixel.coord.x, pixel.coord.y, pixel.color.r, pixel.color.g, pixel.color.b
+        )?;
+        if include_seq {
+            write!(out, ", {}", pixel.seq)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::canvas::Point;
+
+/// A pattern with its bounding box moved to the origin, in coordinates wide
+/// enough that rotating or flipping never overflows.
+pub type Shape = HashSet<(i32, i32)>;
+
+/// The eight rotations and reflections of the square.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum Transform {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipHorizontal,
+    FlipVertical,
+    Transpose,
+    AntiTranspose,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::Transpose,
+        Transform::AntiTranspose,
+    ];
+
+    // rotations are clockwise as drawn, with y growing downward
+    fn apply(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        match self {
+            Transform::Identity => (x, y),
+            Transform::Rotate90 => (-y, x),
+            Transform::Rotate180 => (-x, -y),
+            Transform::Rotate270 => (y, -x),
+            Transform::FlipHorizontal => (-x, y),
+            Transform::FlipVertical => (x, -y),
+            Transform::Transpose => (y, x),
+            Transform::AntiTranspose => (-y, -x),
+        }
+    }
+}
+
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Transform::Identity => "unchanged",
+            Transform::Rotate90 => "rotated 90 degrees clockwise",
+            Transform::Rotate180 => "rotated 180 degrees",
+            Transform::Rotate270 => "rotated 90 degrees counterclockwise",
+            Transform::FlipHorizontal => "mirrored left to right",
+            Transform::FlipVertical => "mirrored top to bottom",
+            Transform::Transpose => "flipped across the main diagonal",
+            Transform::AntiTranspose => "flipped across the anti-diagonal",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Applies `transform` to the points, then moves the result's bounding box to the origin.
+pub fn shape(points: &HashSet<Point>, transform: Transform) -> Shape {
+    let moved: Vec<(i32, i32)> = points
+        .iter()
+        .map(|p| transform.apply((p.x as i32, p.y as i32)))
+        .collect();
+    let min_x = moved.iter().map(|(x, _)| *x).min().unwrap_or(0);
+    let min_y = moved.iter().map(|(_, y)| *y).min().unwrap_or(0);
+    moved
+        .into_iter()
+        .map(|(x, y)| (x - min_x, y - min_y))
+        .collect()
+}
+
+/// The transform of `a` that lines up with as much of `b` as possible, along
+/// with the aligned shapes. An exact match leaves no cells in the symmetric
+/// difference of the two.
+pub fn best_alignment(a: &HashSet<Point>, b: &HashSet<Point>) -> (Transform, Shape, Shape) {
+    let target = shape(b, Transform::Identity);
+    let mut best: Option<(usize, Transform, Shape)> = None;
+    for transform in Transform::ALL.iter() {
+        let candidate = shape(a, *transform);
+        let different = candidate.symmetric_difference(&target).count();
+        if best
+            .as_ref()
+            .is_none_or(|(fewest, _, _)| different < *fewest)
+        {
+            best = Some((different, *transform, candidate));
+        }
+    }
+    let (_, transform, aligned) = best.expect("there is always at least one transform");
+    (transform, aligned, target)
+}
+
+/// Patterns with fewer points than this are never called lattices, since a
+/// handful of scattered points often share a common spacing by chance.
+pub const LATTICE_MIN_POINTS: usize = 8;
+
+/// The spacing `(dx, dy)` of the lattice the points sit on, when every point
+/// satisfies `(x - x0) % dx == 0` and `(y - y0) % dy == 0` from the pattern's
+/// corner with a spacing above 1 on at least one axis. A contiguous shape
+/// always has spacing 1 on both, so it gives `None`.
+pub fn lattice_period(points: &HashSet<Point>) -> Option<(i32, i32)> {
+    if points.len() < LATTICE_MIN_POINTS {
+        return None;
+    }
+    let cells = shape(points, Transform::Identity);
+    // a single row or column has nothing to measure along its other axis
+    let period = |offsets: &mut dyn Iterator<Item = i32>| match offsets.fold(0, gcd) {
+        0 => 1,
+        step => step,
+    };
+    let dx = period(&mut cells.iter().map(|(x, _)| *x));
+    let dy = period(&mut cells.iter().map(|(_, y)| *y));
+    if dx > 1 || dy > 1 {
+        Some((dx, dy))
+    } else {
+        None
+    }
+}
+
+fn gcd(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The most cells `side_by_side` draws for each shape, a 256x256 square's
+/// worth, so patterns logged far apart aren't drawn as gigabytes of text.
+pub const MAX_DRAWN_CELLS: usize = 1 << 16;
+
+/// Draws two shapes side by side: `#` where both have a cell, `x` where only
+/// that side does, and `.` where neither does. Fails when each drawing would
+/// hold more than `MAX_DRAWN_CELLS`.
+pub fn side_by_side(left: &Shape, right: &Shape) -> Result<Vec<String>, String> {
+    let width = left
+        .iter()
+        .chain(right.iter())
+        .map(|(x, _)| x + 1)
+        .max()
+        .unwrap_or(0);
+    let height = left
+        .iter()
+        .chain(right.iter())
+        .map(|(_, y)| y + 1)
+        .max()
+        .unwrap_or(0);
+    let cells = width as usize * height as usize;
+    if cells > MAX_DRAWN_CELLS {
+        return Err(format!(
+            "The aligned patterns span {}x{}, {} cells, more than the {} drawn as text",
+            width, height, cells, MAX_DRAWN_CELLS
+        ));
+    }
+    let row = |shape: &Shape, other: &Shape, y: i32| -> String {
+        (0..width)
+            .map(
+                |x| match (shape.contains(&(x, y)), other.contains(&(x, y))) {
+                    (true, true) => '#',
+                    (true, false) => 'x',
+                    (false, _) => '.',
+                },
+            )
+            .collect()
+    };
+    Ok((0..height)
+        .map(|y| format!("{}   {}", row(left, right, y), row(right, left, y)))
+        .collect())
+}
@@ -0,0 +1,277 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::canvas::{BoundingBox, Point};
+
+/// Sizes of the holes in a region: groups of cells the region does not cover
+/// that are cut off from the outside of its bounding box, top hole first.
+/// Cells connect through their four edge neighbors. The uncovered cells are
+/// handled as gaps between the region's runs, so a sparse region spread
+/// across a huge box costs about as much as its rows and points.
+pub fn holes(points: &HashSet<Point>) -> Vec<usize> {
+    let mut iter = points.iter();
+    let mut bounds = match iter.next() {
+        Some(point) => BoundingBox::new(*point),
+        None => return Vec::new(),
+    };
+    for point in iter {
+        bounds.include(*point);
+    }
+    let (left, right) = (bounds.min.x as i32, bounds.max.x as i32);
+
+    // the uncovered stretches of each row, as (row, first x, last x), top row
+    // first and left to right within a row
+    let mut row_runs = runs(points).into_iter().peekable();
+    let mut gaps: Vec<(i32, i32, i32)> = Vec::new();
+    for y in bounds.min.y as i32..=bounds.max.y as i32 {
+        let mut x = left;
+        while let Some((_, start, length)) = row_runs.next_if(|run| run.0 as i32 == y) {
+            if (start as i32) > x {
+                gaps.push((y, x, start as i32 - 1));
+            }
+            x = start as i32 + length as i32;
+        }
+        if x <= right {
+            gaps.push((y, x, right));
+        }
+    }
+
+    // join gaps that touch across rows; the last set stands for the outside,
+    // which every gap on the edge of the box reaches
+    let outside = gaps.len();
+    let mut sets = DisjointSets::new(gaps.len() + 1);
+    let mut above = 0;
+    for (i, &(y, start, end)) in gaps.iter().enumerate() {
+        if y == bounds.min.y as i32 || y == bounds.max.y as i32 || start == left || end == right {
+            sets.join(i, outside);
+        }
+        // skip gaps from two or more rows up, and those entirely to the left
+        while above < i
+            && (gaps[above].0 < y - 1 || (gaps[above].0 == y - 1 && gaps[above].2 < start))
+        {
+            above += 1;
+        }
+        let mut j = above;
+        while j < i && gaps[j].0 == y - 1 && gaps[j].1 <= end {
+            sets.join(i, j);
+            j += 1;
+        }
+    }
+
+    // sum each remaining set's gaps, in the order each hole first appears
+    let outside = sets.find(outside);
+    let mut sizes: Vec<usize> = Vec::new();
+    let mut hole_of: HashMap<usize, usize> = HashMap::new();
+    for (i, &(_, start, end)) in gaps.iter().enumerate() {
+        let set = sets.find(i);
+        if set == outside {
+            continue;
+        }
+        let hole = *hole_of.entry(set).or_insert_with(|| {
+            sizes.push(0);
+            sizes.len() - 1
+        });
+        sizes[hole] += (end - start + 1) as usize;
+    }
+    sizes
+}
+
+/// Sizes of the separate pieces of a region: groups of its cells that connect
+/// through their four edge neighbors, top piece first. Only the region's own
+/// points are visited, so pieces far apart cost no more than close ones.
+pub fn islands(points: &HashSet<Point>) -> Vec<usize> {
+    split(points).iter().map(Vec::len).collect()
+}
+
+/// The separate pieces of a region, like `islands`, as each piece's size and
+/// its top-left-most point, top piece first.
+pub fn pieces(points: &HashSet<Point>) -> Vec<(usize, Point)> {
+    split(points)
+        .into_iter()
+        .map(|piece| (piece.len(), piece[0]))
+        .collect()
+}
+
+/// The biggest group of points that connect through steps of at most
+/// `reach`, so pieces a short gap apart count as one. Ties go to the group
+/// holding the top-left-most point.
+pub fn largest_cluster(points: &HashSet<Point>, reach: f64) -> HashSet<Point> {
+    // bucket points into cells `reach` wide, so every point in reach of one
+    // is in its cell or a neighboring one
+    let cell_size = reach.ceil().max(1.0) as i32;
+    let cell = |p: &Point| {
+        (
+            (p.x as i32).div_euclid(cell_size),
+            (p.y as i32).div_euclid(cell_size),
+        )
+    };
+    let mut cells: HashMap<(i32, i32), Vec<Point>> = HashMap::new();
+    for point in points {
+        cells.entry(cell(point)).or_default().push(*point);
+    }
+
+    let mut starts: Vec<Point> = points.iter().copied().collect();
+    starts.sort_by_key(|p| (p.y, p.x));
+    let mut seen: HashSet<Point> = HashSet::new();
+    let mut largest: Vec<Point> = Vec::new();
+    for start in starts {
+        if !seen.insert(start) {
+            continue;
+        }
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+        let mut cluster = Vec::new();
+        while let Some(point) = queue.pop_front() {
+            cluster.push(point);
+            let (cx, cy) = cell(&point);
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    for next in cells.get(&(cx + dx, cy + dy)).into_iter().flatten() {
+                        if point.euclidean_distance(next) <= reach && seen.insert(*next) {
+                            queue.push_back(*next);
+                        }
+                    }
+                }
+            }
+        }
+        if cluster.len() > largest.len() {
+            largest = cluster;
+        }
+    }
+    largest.into_iter().collect()
+}
+
+// each piece's points, starting from its top-left-most, top piece first
+fn split(points: &HashSet<Point>) -> Vec<Vec<Point>> {
+    let mut starts: Vec<Point> = points.iter().copied().collect();
+    starts.sort_by_key(|p| (p.y, p.x));
+    let mut seen: HashSet<Point> = HashSet::new();
+    let mut pieces = Vec::new();
+    for start in starts {
+        if !seen.insert(start) {
+            continue;
+        }
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+        let mut piece = Vec::new();
+        while let Some(point) = queue.pop_front() {
+            piece.push(point);
+            for next in point.neighbors().iter() {
+                if points.contains(next) && seen.insert(*next) {
+                    queue.push_back(*next);
+                }
+            }
+        }
+        pieces.push(piece);
+    }
+    pieces
+}
+
+/// The corners of the smallest convex polygon holding every point, in order
+/// around it. Fewer than three points, or points all on one line, give just
+/// the ends.
+pub fn convex_hull(points: &HashSet<Point>) -> Vec<Point> {
+    let mut sorted: Vec<Point> = points.iter().copied().collect();
+    sorted.sort_by_key(|p| (p.x, p.y));
+    if sorted.len() < 3 {
+        return sorted;
+    }
+    // Andrew's monotone chain: the lower half left to right, then the upper
+    // half right to left, dropping points that don't turn the same way
+    let mut hull: Vec<Point> = Vec::new();
+    for half in [sorted.clone(), sorted.into_iter().rev().collect()] {
+        let start = hull.len();
+        for point in half {
+            while hull.len() >= start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], point) <= 0
+            {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // the last point of each half starts the other
+        hull.pop();
+    }
+    hull
+}
+
+/// How far the point is outside a hull from `convex_hull`: 0 inside or on
+/// its edge, otherwise the distance to the nearest edge.
+pub fn hull_distance(hull: &[Point], point: Point) -> f64 {
+    match hull.len() {
+        0 => return f64::INFINITY,
+        1 => return hull[0].euclidean_distance(&point),
+        _ => (),
+    }
+    let edges = || hull.iter().zip(hull.iter().cycle().skip(1));
+    if hull.len() >= 3 && edges().all(|(a, b)| cross(*a, *b, point) >= 0) {
+        return 0.0;
+    }
+    edges()
+        .map(|(a, b)| segment_distance(*a, *b, point))
+        .fold(f64::INFINITY, f64::min)
+}
+
+// positive when o -> a -> b turns counter-clockwise (y up)
+fn cross(o: Point, a: Point, b: Point) -> i64 {
+    let (ax, ay) = (a.x as i64 - o.x as i64, a.y as i64 - o.y as i64);
+    let (bx, by) = (b.x as i64 - o.x as i64, b.y as i64 - o.y as i64);
+    ax * by - ay * bx
+}
+
+fn segment_distance(a: Point, b: Point, point: Point) -> f64 {
+    let (dx, dy) = (b.x as f64 - a.x as f64, b.y as f64 - a.y as f64);
+    let (px, py) = (point.x as f64 - a.x as f64, point.y as f64 - a.y as f64);
+    let length = dx * dx + dy * dy;
+    let t = if length == 0.0 {
+        0.0
+    } else {
+        ((px * dx + py * dy) / length).clamp(0.0, 1.0)
+    };
+    ((px - t * dx).powi(2) + (py - t * dy).powi(2)).sqrt()
+}
+
+/// A region as horizontal runs of cells, `(y, x_start, length)`, top row
+/// first and left to right within a row. Solid regions need far fewer runs
+/// than points.
+pub fn runs(points: &HashSet<Point>) -> Vec<(i16, i16, usize)> {
+    let mut sorted: Vec<(i16, i16)> = points.iter().map(|p| (p.y, p.x)).collect();
+    sorted.sort_unstable();
+    let mut runs: Vec<(i16, i16, usize)> = Vec::new();
+    for (y, x) in sorted {
+        match runs.last_mut() {
+            Some((run_y, start, length))
+                if *run_y == y && *start as i32 + *length as i32 == x as i32 =>
+            {
+                *length += 1
+            }
+            _ => runs.push((y, x, 1)),
+        }
+    }
+    runs
+}
+
+// a union-find over `0..n`, for joining gaps into holes
+struct DisjointSets {
+    parent: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> Self {
+        DisjointSets {
+            parent: (0..n).collect(),
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn join(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parent[a] = b;
+    }
+}
@@ -0,0 +1,718 @@
+//! The checks as a list the binary runs through in order, so checks from
+//! outside this crate can be registered to run alongside the built-in ones.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::canvas::{Artist, BoundingBox, Canvas, Point};
+use crate::checks::{
+    check_all_artists_draw, check_aspect_ratio, check_border_only_artists, check_canvas_full,
+    check_color_count_matches_artists, check_colors_connected, check_colors_derived,
+    check_colors_distinguishable_cvd, check_colors_in_palette, check_colors_unique,
+    check_enough_artists, check_expected_artists, check_ids_contiguous, check_lattice_patterns,
+    check_max_pixels_per_artist, check_no_aliased_points, check_no_coincident_artists,
+    check_no_double_logged_artists, check_no_grayscale_colors, check_no_holes,
+    check_no_mirrored_patterns, check_no_outliers, check_no_overlapping, check_no_protected_pixels,
+    check_no_repeating_patterns, check_no_self_overlap, check_no_shared_rng,
+    check_no_timestamp_gaps, check_pixel_count, check_tids_plausible, check_timestamps_ordered,
+    CheckResults, SelfOverlap, SHARED_RNG_RUN,
+};
+use crate::color::{Color, ColorDerivation, ColorMetric};
+
+/// What a check returns: `Err` with what went wrong and how to fix it.
+pub type CheckResult = Result<(), String>;
+
+/// How many artists the simulation starts, unless the config says otherwise.
+pub const EXPECTED_ARTISTS: usize = 54;
+
+/// How far outside the main drawing a pixel may be, unless the config says
+/// otherwise.
+pub const OUTLIER_DISTANCE: f64 = 10.0;
+
+/// How the checks were asked to judge a log. Opt-in checks only apply when
+/// their switch is on, and checks that need a setting only when it's given.
+#[derive(Debug, Clone, Default)]
+pub struct CheckConfig {
+    /// Cap on how many individual offenders each check lists.
+    pub top_n: Option<usize>,
+    /// The exact number of pixels the log should contain.
+    pub expect_pixels: Option<usize>,
+    /// Count distinct coordinates rather than lines for `expect_pixels`.
+    pub dedupe_pixels: bool,
+    /// The `(width, height)` of the canvas the simulation was meant to paint.
+    pub size: Option<(usize, usize)>,
+    /// Fail unless every pixel of the canvas was painted.
+    pub require_full: bool,
+    /// The width:height ratio the drawing should have.
+    pub expect_aspect: Option<f64>,
+    /// How many artists should paint; `EXPECTED_ARTISTS` when `None`.
+    pub artist_count: Option<usize>,
+    /// The exact set of artist ids expected to appear in the log.
+    pub artist_ids: Option<HashSet<Artist>>,
+    /// The first artist id in a run of ids with no gaps.
+    pub id_base: Option<Artist>,
+    /// The inclusive range real thread ids fall in.
+    pub tid_range: Option<(Artist, Artist)>,
+    /// The fewest pixels any one artist may draw; 1 when `None`.
+    pub min_pixels: Option<usize>,
+    /// The most pixels any one artist may draw.
+    pub max_pixels: Option<usize>,
+    /// Fail when the number of colors and artists differ.
+    pub require_color_per_artist: bool,
+    pub check_cvd: bool,
+    /// How the color-vision check measures how far apart colors are.
+    pub color_metric: ColorMetric,
+    /// How artist colors should follow from their ids, when they should.
+    pub color_from: Option<ColorDerivation>,
+    pub check_grayscale: bool,
+    /// Whether the log's pixels carry timestamps.
+    pub timestamped: bool,
+    pub check_aliasing: bool,
+    pub check_gaps: bool,
+    /// Coordinates no artist may paint on.
+    pub protected: Option<HashSet<Point>>,
+    /// Colors artists must pick from.
+    pub palette: Option<HashSet<Color>>,
+    /// Whether an artist painting over itself fails the run.
+    pub self_overlap: SelfOverlap,
+    /// How many overlapping positions the overlap check tolerates.
+    pub max_overlaps: usize,
+    pub check_color_regions: bool,
+    pub check_outliers: bool,
+    /// `OUTLIER_DISTANCE` when `None`.
+    pub outlier_distance: Option<f64>,
+    pub check_holes: bool,
+    pub check_mirrors: bool,
+    pub check_shared_rng: bool,
+    /// `SHARED_RNG_RUN` when `None`.
+    pub rng_run: Option<usize>,
+    pub check_lattice: bool,
+    pub check_double_logged: bool,
+}
+
+impl CheckConfig {
+    /// A builder starting from the defaults each setter names, which are
+    /// also `CheckConfig::default()`'s.
+    ///
+    /// ```
+    /// use log_reader::registry::CheckConfig;
+    ///
+    /// let config = CheckConfig::builder()
+    ///     .artist_count(4)
+    ///     .size((100, 100))
+    ///     .check_holes(true)
+    ///     .build();
+    /// assert_eq!(config.artist_count, Some(4));
+    /// assert_eq!(config.max_pixels, None);
+    /// ```
+    pub fn builder() -> CheckConfigBuilder {
+        CheckConfigBuilder::default()
+    }
+}
+
+/// Builds a `CheckConfig` one setting at a time. Settings that are off
+/// unless given take either the value or an `Option` of it.
+#[derive(Debug, Clone, Default)]
+pub struct CheckConfigBuilder {
+    config: CheckConfig,
+}
+
+impl CheckConfigBuilder {
+    /// Cap on how many offenders each check lists. Default: no cap.
+    pub fn top_n(mut self, top_n: impl Into<Option<usize>>) -> Self {
+        self.config.top_n = top_n.into();
+        self
+    }
+
+    /// The exact number of pixels the log should contain, for the `pixel-count`
+    /// check. Default: not checked.
+    pub fn expect_pixels(mut self, expect_pixels: impl Into<Option<usize>>) -> Self {
+        self.config.expect_pixels = expect_pixels.into();
+        self
+    }
+
+    /// Count distinct coordinates rather than lines for `expect_pixels`.
+    /// Default: `false`.
+    pub fn dedupe_pixels(mut self, dedupe_pixels: bool) -> Self {
+        self.config.dedupe_pixels = dedupe_pixels;
+        self
+    }
+
+    /// The `(width, height)` of the canvas, for the `full` and `border` checks.
+    /// Default: unknown, so neither runs.
+    pub fn size(mut self, size: impl Into<Option<(usize, usize)>>) -> Self {
+        self.config.size = size.into();
+        self
+    }
+
+    /// Fail unless every pixel of `size` was painted. Default: `false`.
+    pub fn require_full(mut self, require_full: bool) -> Self {
+        self.config.require_full = require_full;
+        self
+    }
+
+    /// The width:height ratio the drawing should have. Default: not checked.
+    pub fn expect_aspect(mut self, expect_aspect: impl Into<Option<f64>>) -> Self {
+        self.config.expect_aspect = expect_aspect.into();
+        self
+    }
+
+    /// How many artists should paint. Default: `EXPECTED_ARTISTS`.
+    pub fn artist_count(mut self, artist_count: impl Into<Option<usize>>) -> Self {
+        self.config.artist_count = artist_count.into();
+        self
+    }
+
+    /// The exact set of artist ids expected in the log. Default: not checked.
+    pub fn artist_ids(mut self, artist_ids: impl Into<Option<HashSet<Artist>>>) -> Self {
+        self.config.artist_ids = artist_ids.into();
+        self
+    }
+
+    /// The first id of a run of artist ids with no gaps. Default: not checked.
+    pub fn id_base(mut self, id_base: impl Into<Option<Artist>>) -> Self {
+        self.config.id_base = id_base.into();
+        self
+    }
+
+    /// The inclusive range real thread ids fall in. Default: not checked.
+    pub fn tid_range(mut self, tid_range: impl Into<Option<(Artist, Artist)>>) -> Self {
+        self.config.tid_range = tid_range.into();
+        self
+    }
+
+    /// The fewest pixels any one artist may draw. Default: 1.
+    pub fn min_pixels(mut self, min_pixels: impl Into<Option<usize>>) -> Self {
+        self.config.min_pixels = min_pixels.into();
+        self
+    }
+
+    /// The most pixels any one artist may draw. Default: no limit.
+    pub fn max_pixels(mut self, max_pixels: impl Into<Option<usize>>) -> Self {
+        self.config.max_pixels = max_pixels.into();
+        self
+    }
+
+    /// Fail, not just warn, when the numbers of colors and artists differ.
+    /// Default: `false`.
+    pub fn require_color_per_artist(mut self, require_color_per_artist: bool) -> Self {
+        self.config.require_color_per_artist = require_color_per_artist;
+        self
+    }
+
+    /// Run the color-vision check. Default: `false`.
+    pub fn check_cvd(mut self, check_cvd: bool) -> Self {
+        self.config.check_cvd = check_cvd;
+        self
+    }
+
+    /// How the color-vision check measures color distance. Default:
+    /// `ColorMetric::Rgb`.
+    pub fn color_metric(mut self, color_metric: ColorMetric) -> Self {
+        self.config.color_metric = color_metric;
+        self
+    }
+
+    /// How artist colors should follow from their ids. Default: not checked.
+    pub fn color_from(mut self, color_from: impl Into<Option<ColorDerivation>>) -> Self {
+        self.config.color_from = color_from.into();
+        self
+    }
+
+    /// Run the grayscale check. Default: `false`.
+    pub fn check_grayscale(mut self, check_grayscale: bool) -> Self {
+        self.config.check_grayscale = check_grayscale;
+        self
+    }
+
+    /// Whether the pixels carry timestamps, which the timestamp checks need.
+    /// Default: `false`.
+    pub fn timestamped(mut self, timestamped: bool) -> Self {
+        self.config.timestamped = timestamped;
+        self
+    }
+
+    /// Run the aliasing check; needs `timestamped`. Default: `false`.
+    pub fn check_aliasing(mut self, check_aliasing: bool) -> Self {
+        self.config.check_aliasing = check_aliasing;
+        self
+    }
+
+    /// Run the timestamp gap check; needs `timestamped`. Default: `false`.
+    pub fn check_gaps(mut self, check_gaps: bool) -> Self {
+        self.config.check_gaps = check_gaps;
+        self
+    }
+
+    /// Coordinates no artist may paint on. Default: none, so the check doesn't
+    /// run.
+    pub fn protected(mut self, protected: impl Into<Option<HashSet<Point>>>) -> Self {
+        self.config.protected = protected.into();
+        self
+    }
+
+    /// Colors artists must pick from. Default: any color.
+    pub fn palette(mut self, palette: impl Into<Option<HashSet<Color>>>) -> Self {
+        self.config.palette = palette.into();
+        self
+    }
+
+    /// Whether an artist painting over itself fails the run. Default:
+    /// `SelfOverlap::Warn`, which doesn't.
+    pub fn self_overlap(mut self, self_overlap: SelfOverlap) -> Self {
+        self.config.self_overlap = self_overlap;
+        self
+    }
+
+    /// How many overlapping positions the overlap check tolerates. Default: 0.
+    pub fn max_overlaps(mut self, max_overlaps: usize) -> Self {
+        self.config.max_overlaps = max_overlaps;
+        self
+    }
+
+    /// Run the color-regions check. Default: `false`.
+    pub fn check_color_regions(mut self, check_color_regions: bool) -> Self {
+        self.config.check_color_regions = check_color_regions;
+        self
+    }
+
+    /// Run the outliers check. Default: `false`.
+    pub fn check_outliers(mut self, check_outliers: bool) -> Self {
+        self.config.check_outliers = check_outliers;
+        self
+    }
+
+    /// How far outside the main drawing a pixel may be. Default:
+    /// `OUTLIER_DISTANCE`.
+    pub fn outlier_distance(mut self, outlier_distance: impl Into<Option<f64>>) -> Self {
+        self.config.outlier_distance = outlier_distance.into();
+        self
+    }
+
+    /// Run the holes check. Default: `false`.
+    pub fn check_holes(mut self, check_holes: bool) -> Self {
+        self.config.check_holes = check_holes;
+        self
+    }
+
+    /// Run the mirrors check. Default: `false`.
+    pub fn check_mirrors(mut self, check_mirrors: bool) -> Self {
+        self.config.check_mirrors = check_mirrors;
+        self
+    }
+
+    /// Run the shared-rng check. Default: `false`.
+    pub fn check_shared_rng(mut self, check_shared_rng: bool) -> Self {
+        self.config.check_shared_rng = check_shared_rng;
+        self
+    }
+
+    /// How many matching moves in a row flag a shared RNG. Default:
+    /// `SHARED_RNG_RUN`.
+    pub fn rng_run(mut self, rng_run: impl Into<Option<usize>>) -> Self {
+        self.config.rng_run = rng_run.into();
+        self
+    }
+
+    /// Run the lattice check. Default: `false`.
+    pub fn check_lattice(mut self, check_lattice: bool) -> Self {
+        self.config.check_lattice = check_lattice;
+        self
+    }
+
+    /// Run the double-logged check. Default: `false`.
+    pub fn check_double_logged(mut self, check_double_logged: bool) -> Self {
+        self.config.check_double_logged = check_double_logged;
+        self
+    }
+
+    pub fn build(self) -> CheckConfig {
+        self.config
+    }
+}
+
+/// Everything a check can look at.
+pub struct AnalysisContext<'a> {
+    pub canvas: &'a Canvas,
+    /// The distinct points each artist painted.
+    pub posns_map: &'a HashMap<Artist, HashSet<Point>>,
+    /// The first color each artist painted with.
+    pub colors_map: &'a HashMap<Artist, Color>,
+    /// The box containing every pixel, or `None` if there were none.
+    pub bounds: Option<BoundingBox>,
+    pub config: &'a CheckConfig,
+}
+
+/// One check a log can pass or fail.
+pub trait Check {
+    /// The name `--only` and `--skip` know the check by.
+    fn name(&self) -> &'static str;
+
+    /// Whether the check was asked for and has the settings it needs. Checks
+    /// that don't apply are left out of the results entirely.
+    fn applies(&self, _ctx: &AnalysisContext) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> CheckResult;
+}
+
+// a built-in check, as the functions in `checks`
+struct BuiltIn {
+    name: &'static str,
+    applies: fn(&AnalysisContext) -> bool,
+    run: fn(&AnalysisContext) -> CheckResult,
+}
+
+impl Check for BuiltIn {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn applies(&self, ctx: &AnalysisContext) -> bool {
+        (self.applies)(ctx)
+    }
+
+    fn run(&self, ctx: &AnalysisContext) -> CheckResult {
+        (self.run)(ctx)
+    }
+}
+
+/// The checks to run, in the order they run.
+#[derive(Default)]
+pub struct Registry {
+    checks: Vec<Box<dyn Check>>,
+}
+
+impl Registry {
+    /// A registry with no checks in it.
+    pub fn new() -> Registry {
+        Registry::default()
+    }
+
+    /// Every built-in check, in the order of `checks::CHECK_NAMES`.
+    pub fn builtin() -> Registry {
+        let mut registry = Registry::new();
+        for (name, applies, run) in builtins() {
+            registry.register(BuiltIn { name, applies, run });
+        }
+        registry
+    }
+
+    /// Adds a check to run after the ones already registered.
+    pub fn register(&mut self, check: impl Check + 'static) {
+        self.checks.push(Box::new(check));
+    }
+
+    pub fn checks(&self) -> impl Iterator<Item = &dyn Check> {
+        self.checks.iter().map(|check| check.as_ref())
+    }
+
+    /// The registered checks named in the comma separated `list`, as `--only`
+    /// and `--skip` take them. Fails on a name no registered check has,
+    /// saying it was found in `source`.
+    ///
+    /// ```
+    /// use log_reader::registry::{AnalysisContext, Check, CheckResult, Registry};
+    ///
+    /// struct Nothing;
+    ///
+    /// impl Check for Nothing {
+    ///     fn name(&self) -> &'static str {
+    ///         "nothing"
+    ///     }
+    ///
+    ///     fn run(&self, _ctx: &AnalysisContext) -> CheckResult {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let mut registry = Registry::builtin();
+    /// registry.register(Nothing);
+    /// assert_eq!(registry.parse_names("overlap, nothing", "--only").unwrap().len(), 2);
+    /// assert!(Registry::builtin().parse_names("nothing", "--only").is_err());
+    /// ```
+    pub fn parse_names(&self, list: &str, source: &str) -> Result<HashSet<&'static str>, String> {
+        let mut names = HashSet::new();
+        for part in list.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let check = self.checks().find(|check| check.name() == part);
+            match check {
+                Some(check) => names.insert(check.name()),
+                None => {
+                    let known: Vec<&str> = self.checks().map(|check| check.name()).collect();
+                    return Err(format!(
+                        "Unknown check {} in {}; known checks are {}",
+                        part,
+                        source,
+                        known.join(", ")
+                    ));
+                }
+            };
+        }
+        Ok(names)
+    }
+
+    /// Runs every registered check that applies and `results` wants, in
+    /// order, recording how each went.
+    pub fn run_all(&self, ctx: &AnalysisContext, results: &mut CheckResults) {
+        for check in self.checks() {
+            if check.applies(ctx) && results.wants(check.name()) {
+                results.run(check.name(), || check.run(ctx));
+            }
+        }
+    }
+}
+
+type Entry = (
+    &'static str,
+    fn(&AnalysisContext) -> bool,
+    fn(&AnalysisContext) -> CheckResult,
+);
+
+fn always(_: &AnalysisContext) -> bool {
+    true
+}
+
+fn builtins() -> Vec<Entry> {
+    vec![
+        // verify that the simulation painted as much as it was supposed to
+        (
+            "pixel-count",
+            |ctx| ctx.config.expect_pixels.is_some(),
+            |ctx| match ctx.config.expect_pixels {
+                Some(expected) => check_pixel_count(ctx.canvas, expected, ctx.config.dedupe_pixels),
+                None => Ok(()),
+            },
+        ),
+        // verify that the whole canvas was filled in, when it should be
+        (
+            "full",
+            |ctx| ctx.config.require_full && ctx.config.size.is_some(),
+            |ctx| match ctx.config.size {
+                Some((width, height)) => check_canvas_full(ctx.canvas, width, height),
+                None => Ok(()),
+            },
+        ),
+        // verify that the drawing has the expected shape, when there is one
+        (
+            "aspect",
+            |ctx| ctx.config.expect_aspect.is_some(),
+            |ctx| match ctx.config.expect_aspect {
+                Some(expected) => check_aspect_ratio(ctx.bounds, expected),
+                None => Ok(()),
+            },
+        ),
+        // verify that a sufficient number of artists exist
+        // possible but highly unlikely this fails due to starvation, not a lack of generation
+        ("artist-count", always, |ctx| {
+            check_enough_artists(
+                &ctx.posns_map.keys().collect::<Vec<_>>()[..],
+                ctx.config.artist_count.unwrap_or(EXPECTED_ARTISTS),
+            )
+        }),
+        // verify that exactly the expected artists showed up, when we know who they are
+        (
+            "expected-artists",
+            |ctx| ctx.config.artist_ids.is_some(),
+            |ctx| match &ctx.config.artist_ids {
+                Some(expected) => check_expected_artists(ctx.posns_map, expected, ctx.config.top_n),
+                None => Ok(()),
+            },
+        ),
+        // verify that artist ids count up from the base, when there is one
+        (
+            "contiguous-ids",
+            |ctx| ctx.config.id_base.is_some(),
+            |ctx| match ctx.config.id_base {
+                Some(base) => check_ids_contiguous(ctx.posns_map, base, ctx.config.top_n),
+                None => Ok(()),
+            },
+        ),
+        // verify that artist ids look like thread ids, when we know what those look like
+        (
+            "tid-range",
+            |ctx| ctx.config.tid_range.is_some(),
+            |ctx| match ctx.config.tid_range {
+                Some((lo, hi)) => check_tids_plausible(ctx.posns_map, lo, hi, ctx.config.top_n),
+                None => Ok(()),
+            },
+        ),
+        // check that all artists draw at least one pixel
+        ("all-draw", always, |ctx| {
+            let min_pixels = ctx.config.min_pixels.unwrap_or(1);
+            check_all_artists_draw(ctx.posns_map, min_pixels, ctx.config.top_n)
+        }),
+        // check that no artist draws past its cap, when there is one
+        (
+            "max-pixels",
+            |ctx| ctx.config.max_pixels.is_some(),
+            |ctx| match ctx.config.max_pixels {
+                Some(max_pixels) => {
+                    check_max_pixels_per_artist(ctx.posns_map, max_pixels, ctx.config.top_n)
+                }
+                None => Ok(()),
+            },
+        ),
+        // verify that colors and artists line up one to one, when they have to
+        (
+            "color-per-artist",
+            |ctx| ctx.config.require_color_per_artist,
+            |ctx| check_color_count_matches_artists(ctx.canvas, ctx.posns_map),
+        ),
+        // verify that all artists have a unique color
+        ("colors", always, |ctx| {
+            check_colors_unique(ctx.canvas, ctx.config.top_n)
+        }),
+        // verify that artist colors can still be told apart with a color-vision deficiency
+        (
+            "cvd",
+            |ctx| ctx.config.check_cvd,
+            |ctx| {
+                check_colors_distinguishable_cvd(
+                    ctx.colors_map,
+                    ctx.config.color_metric,
+                    ctx.config.top_n,
+                )
+            },
+        ),
+        // verify that each artist's color follows from its id, when it should
+        (
+            "derived-colors",
+            |ctx| ctx.config.color_from.is_some(),
+            |ctx| match ctx.config.color_from {
+                Some(derivation) => {
+                    check_colors_derived(ctx.colors_map, derivation, ctx.config.top_n)
+                }
+                None => Ok(()),
+            },
+        ),
+        // verify that every artist picked a real color, when asked to
+        (
+            "grayscale",
+            |ctx| ctx.config.check_grayscale,
+            |ctx| check_no_grayscale_colors(ctx.colors_map, ctx.config.top_n),
+        ),
+        // verify that the log was written in order, when it says when things happened
+        (
+            "timestamps",
+            |ctx| ctx.config.timestamped,
+            |ctx| check_timestamps_ordered(ctx.canvas),
+        ),
+        // look for distinct positions collapsed onto one point, when asked to
+        (
+            "aliasing",
+            |ctx| ctx.config.check_aliasing,
+            |ctx| check_no_aliased_points(ctx.canvas, ctx.config.top_n),
+        ),
+        // look for dropped lines in each artist's timeline, when asked to
+        (
+            "gaps",
+            |ctx| ctx.config.check_gaps,
+            |ctx| check_no_timestamp_gaps(ctx.canvas, ctx.config.top_n),
+        ),
+        // verify that nobody painted inside the protected background
+        (
+            "protected",
+            |ctx| ctx.config.protected.is_some(),
+            |ctx| match &ctx.config.protected {
+                Some(protected) => {
+                    check_no_protected_pixels(ctx.canvas, protected, ctx.config.top_n)
+                }
+                None => Ok(()),
+            },
+        ),
+        // verify that artists stuck to the palette, when there is one
+        (
+            "palette",
+            |ctx| ctx.config.palette.is_some(),
+            |ctx| match &ctx.config.palette {
+                Some(palette) => check_colors_in_palette(ctx.canvas, palette, ctx.config.top_n),
+                None => Ok(()),
+            },
+        ),
+        // verify that no artist paints over itself, when that is an error
+        (
+            "self-overlap",
+            |ctx| ctx.config.self_overlap == SelfOverlap::Error,
+            |ctx| check_no_self_overlap(ctx.canvas, ctx.config.top_n),
+        ),
+        // verify that no artists paint over one another
+        ("overlap", always, |ctx| {
+            check_no_overlapping(
+                ctx.canvas,
+                ctx.posns_map,
+                ctx.config.max_overlaps,
+                ctx.config.top_n,
+            )
+        }),
+        // verify that no two artists painted the very same region
+        ("coincident", always, |ctx| {
+            check_no_coincident_artists(ctx.posns_map, ctx.config.top_n)
+        }),
+        // verify that every color is one blob, when it should be
+        (
+            "color-regions",
+            |ctx| ctx.config.check_color_regions,
+            |ctx| check_colors_connected(ctx.canvas, ctx.config.top_n),
+        ),
+        // look for stray pixels, when asked to
+        (
+            "outliers",
+            |ctx| ctx.config.check_outliers,
+            |ctx| {
+                let max_distance = ctx.config.outlier_distance.unwrap_or(OUTLIER_DISTANCE);
+                check_no_outliers(ctx.posns_map, max_distance, ctx.config.top_n)
+            },
+        ),
+        // verify that artists filled in their regions, when asked to
+        (
+            "holes",
+            |ctx| ctx.config.check_holes,
+            |ctx| check_no_holes(ctx.posns_map, ctx.config.top_n),
+        ),
+        // double check for artists receiving the same random value. this can be done
+        // by analyzing their points, to see if two sets of points are isomorphic
+        // this is impossible if each thread has its own rng, but some patterns may not
+        // show up even if they have the same rng because of competing for pixels within the pattern
+        ("patterns", always, |ctx| {
+            check_no_repeating_patterns(ctx.posns_map, ctx.config.top_n)
+        }),
+        // look specifically for mirror-image copies, when asked to
+        (
+            "mirrors",
+            |ctx| ctx.config.check_mirrors,
+            |ctx| check_no_mirrored_patterns(ctx.posns_map, ctx.config.top_n),
+        ),
+        // look for artists moving in step, as from one shared RNG, when asked to
+        (
+            "shared-rng",
+            |ctx| ctx.config.check_shared_rng,
+            |ctx| {
+                let min_run = ctx.config.rng_run.unwrap_or(SHARED_RNG_RUN);
+                check_no_shared_rng(ctx.canvas, min_run, ctx.config.top_n)
+            },
+        ),
+        // look for strided painting, when asked to
+        (
+            "lattice",
+            |ctx| ctx.config.check_lattice,
+            |ctx| check_lattice_patterns(ctx.posns_map, ctx.config.top_n),
+        ),
+        // look for threads tracing the canvas edge, when there is one
+        (
+            "border",
+            |ctx| ctx.config.size.is_some(),
+            |ctx| match ctx.config.size {
+                Some((width, height)) => {
+                    check_border_only_artists(ctx.posns_map, width, height, ctx.config.top_n)
+                }
+                None => Ok(()),
+            },
+        ),
+        // look for one thread showing up under two ids, when asked to
+        (
+            "double-logged",
+            |ctx| ctx.config.check_double_logged,
+            |ctx| check_no_double_logged_artists(ctx.posns_map, ctx.colors_map, ctx.config.top_n),
+        ),
+    ]
+}
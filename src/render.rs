@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use crate::canvas::{Artist, BoundingBox, Canvas, Origin, Point};
+use crate::color::Color;
+use crate::dense::DenseCanvas;
+use crate::patterns::Shape;
+
+/// What unpainted cells are drawn as.
+pub const BACKGROUND: Color = Color {
+    r: 255,
+    g: 255,
+    b: 255,
+};
+
+/// The most pixels an image of the canvas may have, a 4096x4096 square's
+/// worth, so a few pixels logged far apart can't ask for more memory than
+/// there is.
+pub const MAX_IMAGE_PIXELS: usize = 1 << 24;
+
+/// The widest or tallest an image of the canvas may be, as far as
+/// `Canvas::normalized_to_origin` can shift points.
+pub const MAX_IMAGE_SIDE: u32 = 1 << 15;
+
+/// Which pixel is drawn on a cell that more than one pixel was painted on.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum OverlapResolve {
+    /// The one logged last. The default.
+    #[default]
+    Last,
+    /// The one logged first.
+    First,
+    /// The lowest artist id's.
+    LowestId,
+    /// The brightest color, ties going to the lower artist id.
+    Brightest,
+}
+
+impl FromStr for OverlapResolve {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "last" => Ok(OverlapResolve::Last),
+            "first" => Ok(OverlapResolve::First),
+            "lowest-id" => Ok(OverlapResolve::LowestId),
+            "brightest" => Ok(OverlapResolve::Brightest),
+            _ => Err(format!(
+                "Unknown overlap resolution {}; expected last, first, lowest-id, or brightest",
+                s
+            )),
+        }
+    }
+}
+
+/// How a canvas is turned into an image.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct RenderOptions {
+    /// Which corner the log's `(0, 0)` is in; images are drawn top row first.
+    pub origin: Origin,
+    /// Average the colors of every artist that painted a cell, instead of
+    /// drawing whoever painted it last.
+    pub blend: bool,
+    /// Whose pixel shows on a cell painted more than once, when not blending.
+    /// Only `last` and `first` depend on the order of the log's lines.
+    pub resolve: OverlapResolve,
+}
+
+/// A rendered canvas, one pixel per coordinate of its bounding box, row-major
+/// from the top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Color>,
+}
+
+impl Image {
+    /// Writes the image as a binary PPM (P6).
+    pub fn write_ppm(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        let bytes: Vec<u8> = self
+            .pixels
+            .iter()
+            .flat_map(|color| [color.r, color.g, color.b])
+            .collect();
+        out.write_all(&bytes)
+    }
+}
+
+/// Draws the canvas over its bounding box. Blending only changes what is
+/// drawn; the overlap check still fails on the same logs either way. Fails
+/// when the box holds more than `MAX_IMAGE_PIXELS`.
+pub fn render(canvas: &Canvas, options: &RenderOptions) -> Result<Image, String> {
+    check_image_size(canvas.bounds())?;
+    let (canvas, _) = canvas.normalized_to_origin();
+    let canvas = &canvas;
+    let dense = DenseCanvas::from_canvas(canvas);
+    let bounds = match dense.bounds() {
+        Some(bounds) => bounds,
+        None => {
+            return Ok(Image {
+                width: 0,
+                height: 0,
+                pixels: Vec::new(),
+            })
+        }
+    };
+
+    // every artist's last color on each cell, for averaging or picking one
+    let mut contributors: HashMap<Point, Vec<(Artist, Color)>> = HashMap::new();
+    // the first color logged on each cell
+    let mut first: HashMap<Point, Color> = HashMap::new();
+    let by_artist = options.blend
+        || matches!(
+            options.resolve,
+            OverlapResolve::LowestId | OverlapResolve::Brightest
+        );
+    if options.resolve == OverlapResolve::First {
+        for pixel in canvas {
+            first.entry(pixel.coord).or_insert(pixel.color);
+        }
+    }
+    if by_artist {
+        for pixel in canvas {
+            let cell = contributors.entry(pixel.coord).or_default();
+            match cell.iter_mut().find(|(artist, _)| *artist == pixel.artist) {
+                Some(entry) => entry.1 = pixel.color,
+                None => cell.push((pixel.artist, pixel.color)),
+            }
+        }
+    }
+
+    let mut pixels = Vec::with_capacity(dense.width() * dense.height());
+    for row in 0..dense.height() {
+        for column in 0..dense.width() {
+            let drawn = Point::new(column as i16, row as i16);
+            // flipping is its own inverse, so this finds the logged point too
+            let logged = options.origin.to_top_left(drawn, &bounds);
+            let color = if options.blend {
+                contributors.get(&logged).map(|cell| average(cell))
+            } else {
+                match options.resolve {
+                    OverlapResolve::Last => dense.get(logged).map(|(_, color)| color),
+                    OverlapResolve::First => first.get(&logged).copied(),
+                    OverlapResolve::LowestId => contributors
+                        .get(&logged)
+                        .and_then(|cell| cell.iter().min_by_key(|(artist, _)| *artist))
+                        .map(|(_, color)| *color),
+                    OverlapResolve::Brightest => contributors
+                        .get(&logged)
+                        .and_then(|cell| {
+                            cell.iter().max_by(|(a, a_color), (b, b_color)| {
+                                a_color
+                                    .luminance()
+                                    .total_cmp(&b_color.luminance())
+                                    .then(b.cmp(a))
+                            })
+                        })
+                        .map(|(_, color)| *color),
+                }
+            };
+            pixels.push(color.unwrap_or(BACKGROUND));
+        }
+    }
+    Ok(Image {
+        width: dense.width(),
+        height: dense.height(),
+        pixels,
+    })
+}
+
+// an error for bounds too big to draw, before anything is allocated for them
+fn check_image_size(bounds: Option<BoundingBox>) -> Result<(), String> {
+    let bounds = match bounds {
+        Some(bounds) => bounds,
+        None => return Ok(()),
+    };
+    let pixels = bounds.width() as usize * bounds.height() as usize;
+    if pixels > MAX_IMAGE_PIXELS {
+        return Err(format!(
+            "The canvas spans {} ({}x{}), {} pixels, more than the {} an image may have; check the log for stray coordinates, e.g. with --check-outliers",
+            bounds,
+            bounds.width(),
+            bounds.height(),
+            pixels,
+            MAX_IMAGE_PIXELS
+        ));
+    }
+    if bounds.width() > MAX_IMAGE_SIDE || bounds.height() > MAX_IMAGE_SIDE {
+        return Err(format!(
+            "The canvas spans {} ({}x{}), wider or taller than the {} an image may be; check the log for stray coordinates, e.g. with --check-outliers",
+            bounds,
+            bounds.width(),
+            bounds.height(),
+            MAX_IMAGE_SIDE
+        ));
+    }
+    Ok(())
+}
+
+/// Draws how contended each cell was instead of what was painted on it: blue
+/// where one artist painted, shading linearly to red on the cells the most
+/// artists painted, so overlaps stand out. Unpainted cells are background.
+/// Fails like `render` on canvases too big to draw.
+pub fn render_heatmap(canvas: &Canvas, origin: Origin) -> Result<Image, String> {
+    check_image_size(canvas.bounds())?;
+    let (canvas, _) = canvas.normalized_to_origin();
+    let canvas = &canvas;
+    let dense = DenseCanvas::from_canvas(canvas);
+    let bounds = match dense.bounds() {
+        Some(bounds) => bounds,
+        None => {
+            return Ok(Image {
+                width: 0,
+                height: 0,
+                pixels: Vec::new(),
+            })
+        }
+    };
+
+    let mut painters: HashMap<Point, Vec<Artist>> = HashMap::new();
+    for pixel in canvas {
+        let cell = painters.entry(pixel.coord).or_default();
+        if !cell.contains(&pixel.artist) {
+            cell.push(pixel.artist);
+        }
+    }
+    let most = painters.values().map(Vec::len).max().unwrap_or(1);
+
+    let mut pixels = Vec::with_capacity(dense.width() * dense.height());
+    for row in 0..dense.height() {
+        for column in 0..dense.width() {
+            let drawn = Point::new(column as i16, row as i16);
+            let logged = origin.to_top_left(drawn, &bounds);
+            let color = match painters.get(&logged) {
+                Some(cell) => heat(cell.len(), most),
+                None => BACKGROUND,
+            };
+            pixels.push(color);
+        }
+    }
+    Ok(Image {
+        width: dense.width(),
+        height: dense.height(),
+        pixels,
+    })
+}
+
+// blue for 1 painter up to red for `most`
+fn heat(painters: usize, most: usize) -> Color {
+    let t = if most > 1 {
+        (painters - 1) as f64 / (most - 1) as f64
+    } else {
+        0.0
+    };
+    Color::new(
+        (255.0 * t).round() as u8,
+        0,
+        (255.0 * (1.0 - t)).round() as u8,
+    )
+}
+
+/// Draws two shapes already lined up, like the ones `best_alignment` returns,
+/// as two images of the same size, each shape in its own color, so both
+/// patterns can be compared in the same orientation. Fails when the images
+/// would hold more than `MAX_IMAGE_PIXELS`.
+pub fn render_aligned(
+    left: (&Shape, Color),
+    right: (&Shape, Color),
+) -> Result<(Image, Image), String> {
+    let cells = left.0.iter().chain(right.0.iter());
+    let width = cells.clone().map(|(x, _)| *x + 1).max().unwrap_or(0) as usize;
+    let height = cells.map(|(_, y)| *y + 1).max().unwrap_or(0) as usize;
+    if width * height > MAX_IMAGE_PIXELS {
+        return Err(format!(
+            "The aligned patterns span {}x{}, {} pixels, more than the {} an image may have; check the log for stray coordinates, e.g. with --check-outliers",
+            width,
+            height,
+            width * height,
+            MAX_IMAGE_PIXELS
+        ));
+    }
+    let draw = |(shape, color): (&Shape, Color)| {
+        let mut pixels = vec![BACKGROUND; width * height];
+        for (x, y) in shape {
+            pixels[*y as usize * width + *x as usize] = color;
+        }
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    };
+    Ok((draw(left), draw(right)))
+}
+
+fn average(colors: &[(Artist, Color)]) -> Color {
+    let n = colors.len() as u32;
+    let sum = |channel: fn(&Color) -> u8| -> u8 {
+        let total: u32 = colors.iter().map(|(_, c)| channel(c) as u32).sum();
+        ((total + n / 2) / n) as u8
+    };
+    Color::new(sum(|c| c.r), sum(|c| c.g), sum(|c| c.b))
+}
@@ -0,0 +1,120 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::{self, BufRead, Write};
+
+use crate::anon::{name, parse_name};
+use crate::canvas::{Artist, BoundingBox, Canvas, Point};
+use crate::color::Color;
+use crate::stats::perimeter;
+
+const HELP: &str = "Commands:
+  artist <id>        show an artist's pixel count, color, bounds, and perimeter
+  point <x> <y>      show which artists painted at (x, y)
+  color <r> <g> <b>  show which artists painted with this color
+  help               show this message
+  quit               exit";
+
+/// Answers line-based queries about an analyzed log until `quit` or the end of `input`.
+pub fn run_repl(
+    canvas: &Canvas,
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    colors_map: &HashMap<Artist, Color>,
+    input: impl BufRead,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut painters: HashMap<Point, BTreeSet<Artist>> = HashMap::new();
+    let mut color_users: HashMap<Color, BTreeSet<Artist>> = HashMap::new();
+    for pixel in canvas {
+        painters
+            .entry(pixel.coord)
+            .or_default()
+            .insert(pixel.artist);
+        color_users
+            .entry(pixel.color)
+            .or_default()
+            .insert(pixel.artist);
+    }
+
+    writeln!(out, "Type a command, or \"help\" for a list of commands.")?;
+    write!(out, "> ")?;
+    out.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [] => (),
+            ["quit"] | ["exit"] => break,
+            ["help"] => writeln!(out, "{}", HELP)?,
+            ["artist", id] => match parse_name(id) {
+                Some(artist) => match posns_map.get(&artist) {
+                    Some(points) => describe_artist(artist, points, colors_map, out)?,
+                    None => writeln!(out, "Artist {} never painted.", name(artist))?,
+                },
+                None => writeln!(out, "Invalid artist id {}", id)?,
+            },
+            ["point", x, y] => match (x.parse::<i16>(), y.parse::<i16>()) {
+                (Ok(x), Ok(y)) => match painters.get(&Point::new(x, y)) {
+                    Some(artists) => writeln!(
+                        out,
+                        "({}, {}) was painted by {}",
+                        x,
+                        y,
+                        list_artists(artists)
+                    )?,
+                    None => writeln!(out, "Nobody painted ({}, {}).", x, y)?,
+                },
+                _ => writeln!(out, "Invalid point {} {}", x, y)?,
+            },
+            ["color", r, g, b] => match (r.parse::<u8>(), g.parse::<u8>(), b.parse::<u8>()) {
+                (Ok(r), Ok(g), Ok(b)) => {
+                    let color = Color::new(r, g, b);
+                    match color_users.get(&color) {
+                        Some(artists) => {
+                            writeln!(out, "Color {} was used by {}", color, list_artists(artists))?
+                        }
+                        None => writeln!(out, "Nobody painted with color {}.", color)?,
+                    }
+                }
+                _ => writeln!(out, "Invalid color {} {} {}", r, g, b)?,
+            },
+            _ => writeln!(out, "Unknown command {:?}; try \"help\".", line.trim())?,
+        }
+        write!(out, "> ")?;
+        out.flush()?;
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+fn describe_artist(
+    artist: Artist,
+    points: &HashSet<Point>,
+    colors_map: &HashMap<Artist, Color>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut points_iter = points.iter();
+    let mut bounds = match points_iter.next() {
+        Some(point) => BoundingBox::new(*point),
+        None => return writeln!(out, "Artist {} never painted.", name(artist)),
+    };
+    for point in points_iter {
+        bounds.include(*point);
+    }
+    writeln!(
+        out,
+        "Artist {}: {} pixels in color {}, spanning {}, perimeter {}",
+        name(artist),
+        points.len(),
+        colors_map[&artist],
+        bounds,
+        perimeter(points)
+    )
+}
+
+fn list_artists(artists: &BTreeSet<Artist>) -> String {
+    let names: Vec<String> = artists.iter().map(|a| name(*a).to_string()).collect();
+    if names.len() == 1 {
+        format!("artist {}", names[0])
+    } else {
+        format!("artists {}", names.join(", "))
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use serde::{Serialize, Serializer};
+use serde_json::ser::PrettyFormatter;
+
+use crate::anon::{self, name};
+use crate::canvas::{Artist, BoundingBox, Point};
+use crate::checks::CheckResults;
+use crate::color::Color;
+use crate::regions::islands;
+use crate::term::json_pretty;
+
+/// How the results of a run are written to stdout.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// The human-readable report. The default.
+    #[default]
+    Text,
+    /// One JSON object per line: an `artist` record per artist, then a
+    /// `summary`. The human-readable report moves to stderr.
+    Ndjson,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(ReportFormat::Text),
+            "ndjson" => Ok(ReportFormat::Ndjson),
+            _ => Err(format!("Unknown format {}; expected text or ndjson", s)),
+        }
+    }
+}
+
+/// One line of machine-readable output. The `type` field says which kind it is.
+/// Field names are part of the output format; only add to them.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Record {
+    Artist(ArtistReport),
+    Summary(Summary),
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArtistReport {
+    /// A number, or the pseudonym as a string with `--anonymize`.
+    #[serde(serialize_with = "serialize_artist")]
+    pub id: Artist,
+    /// Distinct coordinates the artist painted.
+    pub pixels: usize,
+    /// The first color the artist painted with.
+    pub color: Option<Color>,
+    pub bounds: BoundingBox,
+    /// How many separate pieces the artist's region is in.
+    pub islands: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    pub artists: usize,
+    /// Pixels in the log, counting repaints.
+    pub pixels: usize,
+    pub passed: bool,
+    pub checks: usize,
+    pub failed: Vec<&'static str>,
+    pub skipped: Vec<&'static str>,
+}
+
+/// A report for each artist, by id.
+pub fn artist_reports(
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    colors_map: &HashMap<Artist, Color>,
+) -> Vec<ArtistReport> {
+    let mut artists: Vec<&Artist> = posns_map.keys().collect();
+    artists.sort();
+    artists
+        .into_iter()
+        .filter_map(|artist| {
+            let points = &posns_map[artist];
+            let mut iter = points.iter();
+            let mut bounds = BoundingBox::new(*iter.next()?);
+            iter.for_each(|p| bounds.include(*p));
+            Some(ArtistReport {
+                id: *artist,
+                pixels: points.len(),
+                color: colors_map.get(artist).copied(),
+                bounds,
+                islands: islands(points).len(),
+            })
+        })
+        .collect()
+}
+
+fn serialize_artist<S: Serializer>(artist: &Artist, serializer: S) -> Result<S::Ok, S::Error> {
+    if anon::enabled() {
+        serializer.collect_str(&name(*artist))
+    } else {
+        serializer.serialize_u64(*artist)
+    }
+}
+
+pub fn run_summary(results: &CheckResults, artists: usize, pixels: usize) -> Summary {
+    Summary {
+        artists,
+        pixels,
+        passed: results.failures() == 0,
+        checks: results.len(),
+        failed: results.failed().collect(),
+        skipped: results.skipped().to_vec(),
+    }
+}
+
+/// How one log of a batch went: its summary, or why it couldn't be analyzed.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum FileReport {
+    Analyzed(Summary),
+    Failed { error: String },
+}
+
+/// Writes `value` as JSON, pretty-printed or compact as `term::json_pretty`
+/// says. Every JSON writer goes through here.
+pub fn write_json(value: &impl Serialize, out: &mut impl Write) -> io::Result<()> {
+    if json_pretty() {
+        serde_json::to_writer_pretty(&mut *out, value)?;
+    } else {
+        serde_json::to_writer(&mut *out, value)?;
+    }
+    Ok(())
+}
+
+/// Writes one JSON object keyed by file name, each holding that log's
+/// summary or an `error`.
+pub fn write_batch_json(
+    reports: &BTreeMap<String, FileReport>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    write_json(reports, out)?;
+    writeln!(out)
+}
+
+/// Writes `posns_map` as one JSON object from artist to the points it painted,
+/// artists in id order and each one's points sorted, so the same log always
+/// gives the same file.
+pub fn write_posns_json(
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut artists: Vec<(&Artist, Vec<Point>)> = posns_map
+        .iter()
+        .map(|(artist, points)| {
+            let mut points: Vec<Point> = points.iter().copied().collect();
+            // Point's own order only looks at x
+            points.sort_by_key(|p| (p.x, p.y));
+            (artist, points)
+        })
+        .collect();
+    artists.sort_by_key(|(artist, _)| **artist);
+    // keys go through `name` so --anonymize hides them here too
+    let entries = artists
+        .iter()
+        .map(|(artist, points)| (name(**artist).to_string(), points));
+    if json_pretty() {
+        let formatter = PrettyFormatter::new();
+        serde_json::Serializer::with_formatter(&mut *out, formatter).collect_map(entries)?;
+    } else {
+        serde_json::Serializer::new(&mut *out).collect_map(entries)?;
+    }
+    writeln!(out)
+}
+
+/// Writes each record as a line of JSON. Pretty-printed records span lines,
+/// so only compact output is NDJSON proper.
+pub fn write_ndjson(records: &[Record], out: &mut impl Write) -> io::Result<()> {
+    for record in records {
+        write_json(record, out)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,609 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::anon::name;
+use crate::canvas::{Artist, BoundingBox, Canvas, Origin, Point};
+use crate::color::Color;
+use crate::parse::{LockMarker, SkippedPaint};
+use crate::regions::runs;
+use crate::term::deterministic;
+use crate::{say, warn};
+
+pub fn print_artist_stats(posns_map: &HashMap<Artist, HashSet<Point>>) {
+    say!("Artist statistics:");
+    let mut artists: Vec<&Artist> = posns_map.keys().collect();
+    artists.sort();
+    for artist in artists {
+        let points = &posns_map[artist];
+        let edges = perimeter(points);
+        say!(
+            "Artist {}: {} pixels, perimeter {} ({:.2} edges per pixel)",
+            name(*artist),
+            points.len(),
+            edges,
+            edges as f64 / points.len() as f64
+        );
+        if let Some(circle) = enclosing_circle(points) {
+            say!(
+                "    enclosing circle centered at ({:.2}, {:.2}), radius {:.2}",
+                circle.center.0,
+                circle.center.1,
+                circle.radius
+            );
+        }
+    }
+    let counts: Vec<usize> = posns_map.values().map(HashSet::len).collect();
+    say!(
+        "Gini coefficient of pixels per artist: {:.3} (0 is perfectly balanced)",
+        gini(&counts)
+    );
+
+    // the outline of the whole drawing, against the unpainted background
+    let painted: HashSet<Point> = posns_map.values().flatten().copied().collect();
+    let edges = perimeter(&painted);
+    say!(
+        "Whole canvas: {} pixels, perimeter {} ({:.2} edges per pixel)",
+        painted.len(),
+        edges,
+        edges as f64 / painted.len().max(1) as f64
+    );
+
+    // each overlapped coordinate counts once per artist in the per-artist sum
+    let summed: usize = counts.iter().sum();
+    say!(
+        "Painted area: {} distinct coordinates, against {} summed over artists, {} more from overlaps",
+        painted.len(),
+        summed,
+        summed - painted.len()
+    );
+}
+
+/// Gini coefficient of a set of counts, from 0 when every count is equal up
+/// towards 1 when one entry holds everything.
+pub fn gini(counts: &[usize]) -> f64 {
+    let total: usize = counts.iter().sum();
+    if counts.len() < 2 || total == 0 {
+        return 0.0;
+    }
+    let mut sorted = counts.to_vec();
+    sorted.sort_unstable();
+    // mean absolute difference over every pair, via the sorted rank form
+    let n = sorted.len() as f64;
+    let weighted: f64 = sorted
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| (2.0 * (i as f64 + 1.0) - n - 1.0) * c as f64)
+        .sum();
+    weighted / (n * total as f64)
+}
+
+/// Each artist with the sequence number of the last pixel they painted,
+/// earliest finisher first; ties go to the lower artist id.
+pub fn completion_order(canvas: &Canvas) -> Vec<(Artist, usize)> {
+    let mut last_seen: HashMap<Artist, usize> = HashMap::new();
+    for pixel in canvas {
+        let seq = last_seen.entry(pixel.artist).or_insert(pixel.seq);
+        *seq = (*seq).max(pixel.seq);
+    }
+    let mut order: Vec<(Artist, usize)> = last_seen.into_iter().collect();
+    order.sort_by_key(|(artist, seq)| (*seq, *artist));
+    order
+}
+
+/// Who painted the very first and the very last pixel of the log, by
+/// sequence number. An artist that is always first or last across runs is a
+/// hint about how threads are started or joined.
+pub fn print_first_and_last(canvas: &Canvas) {
+    let first = canvas.iter().min_by_key(|p| p.seq);
+    let last = canvas.iter().max_by_key(|p| p.seq);
+    if let (Some(first), Some(last)) = (first, last) {
+        say!(
+            "First pixel: artist {} at ({}, {}). Last pixel: artist {} at ({}, {}).",
+            name(first.artist),
+            first.coord.x,
+            first.coord.y,
+            name(last.artist),
+            last.coord.x,
+            last.coord.y
+        );
+    }
+}
+
+/// How many coordinates were painted once, twice, and so on, as
+/// `(times painted, coordinates)` from once up. A clean log has every
+/// coordinate painted once; anything higher is an overlap or a repaint.
+pub fn paint_count_histogram(canvas: &Canvas) -> Vec<(usize, usize)> {
+    let mut tally: HashMap<Point, usize> = HashMap::new();
+    for pixel in canvas {
+        *tally.entry(pixel.coord).or_default() += 1;
+    }
+    let mut histogram: BTreeMap<usize, usize> = BTreeMap::new();
+    for times in tally.values() {
+        *histogram.entry(*times).or_default() += 1;
+    }
+    histogram.into_iter().collect()
+}
+
+pub fn print_paint_counts(canvas: &Canvas) {
+    let histogram = paint_count_histogram(canvas);
+    let coordinates: usize = histogram.iter().map(|(_, count)| count).sum();
+    say!("Times each coordinate was painted:");
+    for (times, count) in histogram {
+        say!(
+            "{:>5}x {:>7} coordinates ({:.1}%)",
+            times,
+            count,
+            100.0 * count as f64 / coordinates as f64
+        );
+    }
+}
+
+/// How many paints were skipped on locked positions, by marker and by artist,
+/// most skipped artist first.
+pub fn print_skipped_paints(skipped: &[SkippedPaint]) {
+    let locked = skipped
+        .iter()
+        .filter(|paint| paint.marker == LockMarker::Locked)
+        .count();
+    let positions: HashSet<Point> = skipped.iter().map(|paint| paint.coord).collect();
+    say!(
+        "{} paints were skipped ({} {}, {} {}), on {} positions",
+        skipped.len(),
+        locked,
+        LockMarker::Locked,
+        skipped.len() - locked,
+        LockMarker::Skipped,
+        positions.len()
+    );
+    let mut by_artist: HashMap<Artist, usize> = HashMap::new();
+    for paint in skipped {
+        *by_artist.entry(paint.artist).or_default() += 1;
+    }
+    let mut by_artist: Vec<(Artist, usize)> = by_artist.into_iter().collect();
+    by_artist.sort_by_key(|(artist, count)| (std::cmp::Reverse(*count), *artist));
+    for (artist, count) in by_artist {
+        say!("Artist {}: {} skipped", name(artist), count);
+    }
+}
+
+/// Artists that painted exactly one pixel, with where, by id. These are
+/// usually threads that starved or failed right after starting.
+pub fn single_pixel_artists(posns_map: &HashMap<Artist, HashSet<Point>>) -> Vec<(Artist, Point)> {
+    let mut singles: Vec<(Artist, Point)> = posns_map
+        .iter()
+        .filter(|(_, points)| points.len() == 1)
+        .filter_map(|(artist, points)| Some((*artist, *points.iter().next()?)))
+        .collect();
+    singles.sort_by_key(|(artist, _)| *artist);
+    singles
+}
+
+pub fn print_completion_order(canvas: &Canvas) {
+    say!("Completion order (by sequence number of each artist's last pixel):");
+    for (place, (artist, seq)) in completion_order(canvas).iter().enumerate() {
+        say!(
+            "{:>4}. Artist {} finished at pixel {}",
+            place + 1,
+            name(*artist),
+            seq
+        );
+    }
+}
+
+/// Pairs of artists whose bounding boxes intersect but who never painted the
+/// same point, lower id first, with whether their pixels share an edge.
+/// These artists interleave rather than staying in separate territories.
+pub fn interleaving_artists(
+    posns_map: &HashMap<Artist, HashSet<Point>>,
+) -> Vec<(Artist, Artist, bool)> {
+    let mut boxes: Vec<(Artist, BoundingBox)> = posns_map
+        .iter()
+        .filter_map(|(artist, points)| {
+            let mut points = points.iter();
+            let mut bounds = BoundingBox::new(*points.next()?);
+            points.for_each(|p| bounds.include(*p));
+            Some((*artist, bounds))
+        })
+        .collect();
+    boxes.sort_by_key(|(artist, _)| *artist);
+
+    let mut pairs = Vec::new();
+    for (i, (artist, bounds)) in boxes.iter().enumerate() {
+        for (other, other_bounds) in &boxes[i + 1..] {
+            // the boxes are the cheap filter; only then compare pixels
+            if !bounds.intersects(other_bounds) {
+                continue;
+            }
+            let (points, other_points) = (&posns_map[artist], &posns_map[other]);
+            if !points.is_disjoint(other_points) {
+                continue;
+            }
+            let touching = points
+                .iter()
+                .any(|p| p.neighbors().iter().any(|n| other_points.contains(n)));
+            pairs.push((*artist, *other, touching));
+        }
+    }
+    pairs
+}
+
+pub fn print_runs(posns_map: &HashMap<Artist, HashSet<Point>>) {
+    say!("Horizontal runs of each artist's pixels, as (y, x_start, length):");
+    let mut artists: Vec<&Artist> = posns_map.keys().collect();
+    artists.sort();
+    for artist in artists {
+        let runs: Vec<String> = runs(&posns_map[artist])
+            .iter()
+            .map(|(y, x, length)| format!("({}, {}, {})", y, x, length))
+            .collect();
+        say!("Artist {}: {}", name(*artist), runs.join(" "));
+    }
+}
+
+pub fn print_interleaving(posns_map: &HashMap<Artist, HashSet<Point>>) {
+    let pairs = interleaving_artists(posns_map);
+    say!(
+        "Interleaving artists (bounding boxes overlap, pixels don't): {} pairs",
+        pairs.len()
+    );
+    for (artist, other, touching) in pairs {
+        say!(
+            "Artists {} and {}: {}",
+            name(artist),
+            name(other),
+            if touching {
+                "pixels touch"
+            } else {
+                "pixels never touch"
+            }
+        );
+    }
+}
+
+/// How far under the average a quadrant's pixel count may fall, as a fraction
+/// of it, before `print_quadrants` warns.
+pub const QUADRANT_MIN_FRACTION: f64 = 0.5;
+
+/// How many distinct painted coordinates fall in each quadrant of `bounds`,
+/// as top-left, top-right, bottom-left, and bottom-right, as drawn for
+/// `origin`. With an odd width or height the middle column or row goes to
+/// the right or bottom half.
+pub fn quadrant_counts(canvas: &Canvas, bounds: &BoundingBox, origin: Origin) -> [usize; 4] {
+    let painted: HashSet<Point> = canvas.iter().map(|pixel| pixel.coord).collect();
+    let mut counts = [0; 4];
+    for point in painted {
+        let drawn = origin.to_top_left(point, bounds);
+        let right = (drawn.x as i32 - bounds.min.x as i32) as u32 >= bounds.width() / 2;
+        let bottom = (drawn.y as i32 - bounds.min.y as i32) as u32 >= bounds.height() / 2;
+        counts[2 * bottom as usize + right as usize] += 1;
+    }
+    counts
+}
+
+/// Prints how many pixels each quadrant of the canvas holds, warning about
+/// any with fewer than `min_fraction` of the average, for assignments that
+/// expect the drawing spread evenly.
+pub fn print_quadrants(
+    canvas: &Canvas,
+    bounds: Option<BoundingBox>,
+    origin: Origin,
+    min_fraction: f64,
+) {
+    let bounds = match bounds {
+        Some(bounds) => bounds,
+        None => {
+            say!("No pixels to split into quadrants.");
+            return;
+        }
+    };
+    let counts = quadrant_counts(canvas, &bounds, origin);
+    let average = counts.iter().sum::<usize>() as f64 / 4.0;
+    say!("Pixels per quadrant of {}:", bounds);
+    let names = ["Top left", "Top right", "Bottom left", "Bottom right"];
+    for (quadrant, count) in names.iter().zip(counts.iter()) {
+        say!("  {}: {}", quadrant, count);
+    }
+    for (quadrant, count) in names.iter().zip(counts.iter()) {
+        if (*count as f64) < average * min_fraction {
+            warn!(
+                "the {} quadrant has {} pixels, under {:.0}% of the average {:.1}; the drawing is lopsided.",
+                quadrant.to_lowercase(),
+                count,
+                100.0 * min_fraction,
+                average
+            );
+        }
+    }
+}
+
+/// Splits the canvas bounds into `cell_size` squares and reports the ones with
+/// the largest and smallest fraction of painted pixels.
+pub fn print_density(canvas: &Canvas, cell_size: usize) {
+    let mut iter = canvas.iter();
+    let mut bounds = match iter.next() {
+        Some(pixel) => BoundingBox::new(pixel.coord),
+        None => {
+            say!("No pixels to compute density over.");
+            return;
+        }
+    };
+    iter.for_each(|pixel| bounds.include(pixel.coord));
+    let cell_size = cell_size.max(1);
+    let (width, height) = (bounds.width() as usize, bounds.height() as usize);
+    let columns = width.div_ceil(cell_size);
+    let rows = height.div_ceil(cell_size);
+    // painted pixels per cell that has any, by row-major index; a spread out
+    // log has far too many cells to lay them all out
+    let painted: HashSet<Point> = canvas.iter().map(|pixel| pixel.coord).collect();
+    let mut counts: HashMap<usize, usize> = HashMap::new();
+    for point in painted {
+        let column = (point.x as i32 - bounds.min.x as i32) as usize / cell_size;
+        let row = (point.y as i32 - bounds.min.y as i32) as usize / cell_size;
+        *counts.entry(row * columns + column).or_default() += 1;
+    }
+    let mut counts: Vec<(usize, usize)> = counts.into_iter().collect();
+    counts.sort();
+    // cells on the right and bottom edges may be cut short
+    let total = |i: usize| {
+        let cell_width = cell_size.min(width - i % columns * cell_size);
+        let cell_height = cell_size.min(height - i / columns * cell_size);
+        cell_width * cell_height
+    };
+    let density = |(i, painted): (usize, usize)| painted as f64 / total(i) as f64;
+
+    // ties go to the first cell in row-major order
+    let mut densest = counts[0];
+    let mut sparsest = counts[0];
+    for cell in &counts {
+        if density(*cell) > density(densest) {
+            densest = *cell;
+        }
+        if density(*cell) < density(sparsest) {
+            sparsest = *cell;
+        }
+    }
+    // an unpainted cell beats any painted one; the first is the first gap
+    if counts.len() < columns * rows {
+        let empty = counts
+            .iter()
+            .enumerate()
+            .find(|(expected, (i, _))| expected != i)
+            .map_or(counts.len(), |(expected, _)| expected);
+        sparsest = (empty, 0);
+    }
+    say!(
+        "Density over {}x{} cells of {}:",
+        cell_size,
+        cell_size,
+        bounds
+    );
+    for (name, cell) in [("Densest", densest), ("Sparsest", sparsest)].iter() {
+        let (i, painted) = *cell;
+        let x = bounds.min.x as i32 + (i % columns * cell_size) as i32;
+        let y = bounds.min.y as i32 + (i / columns * cell_size) as i32;
+        say!(
+            "{} cell at ({}, {}): {} of {} pixels painted ({:.0}%)",
+            name,
+            x,
+            y,
+            painted,
+            total(i),
+            100.0 * density(*cell)
+        );
+    }
+}
+
+// width of each luminance histogram bucket
+const LUMINANCE_BUCKET: f64 = 32.0;
+
+pub fn print_color_stats(canvas: &Canvas, colors_map: &HashMap<Artist, Color>) {
+    say!("Color statistics:");
+    if let Some((color, cells, painted)) = dominant_color(canvas) {
+        say!(
+            "Dominant color: {}, showing on {} of {} painted cells ({:.1}%)",
+            color,
+            cells,
+            painted,
+            100.0 * cells as f64 / painted as f64
+        );
+    }
+    print_color_regions(canvas);
+    if colors_map.is_empty() {
+        say!("No artist colors to summarize.");
+        return;
+    }
+    let mut colors: Vec<(&Artist, &Color)> = colors_map.iter().collect();
+    if deterministic() {
+        // so the floating point sums add up in the same order
+        colors.sort_by_key(|(artist, _)| **artist);
+    }
+    let luminances: Vec<f64> = colors.iter().map(|(_, c)| c.luminance()).collect();
+    let lowest = luminances.iter().cloned().fold(f64::INFINITY, f64::min);
+    let highest = luminances.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let mean = luminances.iter().sum::<f64>() / luminances.len() as f64;
+    say!(
+        "Luminance of artist colors: min {:.1}, max {:.1}, mean {:.1}",
+        lowest,
+        highest,
+        mean
+    );
+
+    let buckets = (256.0 / LUMINANCE_BUCKET) as usize;
+    let mut histogram = vec![0; buckets];
+    for luminance in &luminances {
+        let bucket = ((luminance / LUMINANCE_BUCKET) as usize).min(buckets - 1);
+        histogram[bucket] += 1;
+    }
+    for (bucket, count) in histogram.iter().enumerate() {
+        let start = bucket as f64 * LUMINANCE_BUCKET;
+        say!(
+            "{:>5.0}-{:<5.0} {:>4} {}",
+            start,
+            start + LUMINANCE_BUCKET,
+            count,
+            "#".repeat(*count)
+        );
+    }
+}
+
+/// The color showing on the most cells once every repaint is drawn, with how
+/// many cells it shows on and how many are painted at all. Unlike the pixel
+/// counts, cells painted over by another color count for the color on top
+/// only. Ties go to the lowest `(r, g, b)`.
+pub fn dominant_color(canvas: &Canvas) -> Option<(Color, usize, usize)> {
+    // the color on top of each cell, the last one logged there
+    let mut top: HashMap<Point, Color> = HashMap::new();
+    for pixel in canvas {
+        top.insert(pixel.coord, pixel.color);
+    }
+    let mut cells: HashMap<Color, usize> = HashMap::new();
+    for color in top.values() {
+        *cells.entry(*color).or_default() += 1;
+    }
+    cells
+        .into_iter()
+        .min_by_key(|(color, count)| (std::cmp::Reverse(*count), color.r, color.g, color.b))
+        .map(|(color, count)| (color, count, top.len()))
+}
+
+// how many pixels each color covers, and where; with one color per artist
+// these match the artists' own regions, so a color spanning far more of the
+// canvas than expected points at artists sharing it
+fn print_color_regions(canvas: &Canvas) {
+    let mut regions: HashMap<Color, (usize, BoundingBox)> = HashMap::new();
+    for pixel in canvas {
+        regions
+            .entry(pixel.color)
+            .and_modify(|(count, bounds)| {
+                *count += 1;
+                bounds.include(pixel.coord);
+            })
+            .or_insert((1, BoundingBox::new(pixel.coord)));
+    }
+    let mut regions: Vec<(Color, (usize, BoundingBox))> = regions.into_iter().collect();
+    regions
+        .sort_by_key(|(color, (count, _))| (std::cmp::Reverse(*count), color.r, color.g, color.b));
+    for (color, (count, bounds)) in regions {
+        say!(
+            "Color {}: {} pixels, spanning {} ({}x{})",
+            color,
+            count,
+            bounds,
+            bounds.width(),
+            bounds.height()
+        );
+    }
+}
+
+/// How many of the coordinates `(0, 0)` to `(width - 1, height - 1)` were
+/// painted, out of `width * height`. Paints outside the canvas don't count.
+pub fn coverage(canvas: &Canvas, width: usize, height: usize) -> (usize, usize) {
+    let painted: HashSet<Point> = canvas
+        .iter()
+        .map(|p| p.coord)
+        .filter(|p| p.x >= 0 && p.y >= 0 && (p.x as usize) < width && (p.y as usize) < height)
+        .collect();
+    (painted.len(), width * height)
+}
+
+/// A circle in canvas coordinates.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Circle {
+    pub center: (f64, f64),
+    pub radius: f64,
+}
+
+impl Circle {
+    // slightly generous, so points the circle was built from count as inside
+    fn contains(&self, (x, y): (f64, f64)) -> bool {
+        (x - self.center.0).hypot(y - self.center.1) <= self.radius + 1e-9
+    }
+
+    fn through_two(a: (f64, f64), b: (f64, f64)) -> Circle {
+        let center = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+        Circle {
+            center,
+            radius: (a.0 - center.0).hypot(a.1 - center.1),
+        }
+    }
+
+    // the circumcircle, or the widest two-point circle when the points are
+    // in a line
+    fn through_three(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> Circle {
+        let d = 2.0 * (a.0 * (b.1 - c.1) + b.0 * (c.1 - a.1) + c.0 * (a.1 - b.1));
+        if d.abs() < 1e-12 {
+            return [
+                Circle::through_two(a, b),
+                Circle::through_two(a, c),
+                Circle::through_two(b, c),
+            ]
+            .iter()
+            .copied()
+            .max_by(|x, y| x.radius.total_cmp(&y.radius))
+            .unwrap();
+        }
+        let norm = |p: (f64, f64)| p.0 * p.0 + p.1 * p.1;
+        let x = (norm(a) * (b.1 - c.1) + norm(b) * (c.1 - a.1) + norm(c) * (a.1 - b.1)) / d;
+        let y = (norm(a) * (c.0 - b.0) + norm(b) * (a.0 - c.0) + norm(c) * (b.0 - a.0)) / d;
+        Circle {
+            center: (x, y),
+            radius: (a.0 - x).hypot(a.1 - y),
+        }
+    }
+}
+
+/// The smallest circle containing every point, by Welzl's algorithm in its
+/// iterative move-to-front form, or `None` for no points. Unlike a bounding
+/// box, its radius doesn't change when the pattern is rotated.
+pub fn enclosing_circle(points: &HashSet<Point>) -> Option<Circle> {
+    let mut points: Vec<(f64, f64)> = points.iter().map(|p| (p.x as f64, p.y as f64)).collect();
+    match points.len() {
+        0 => return None,
+        1 => {
+            return Some(Circle {
+                center: points[0],
+                radius: 0.0,
+            })
+        }
+        2 => return Some(Circle::through_two(points[0], points[1])),
+        _ => (),
+    }
+    // Welzl's expected linear time needs the points in random order; a fixed
+    // seed keeps the result the same from run to run
+    points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mut state: u64 = 0x9e3779b97f4a7c15;
+    for i in (1..points.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        points.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+
+    let mut circle = Circle::through_two(points[0], points[0]);
+    for i in 1..points.len() {
+        if circle.contains(points[i]) {
+            continue;
+        }
+        // points[i] is on the boundary of the circle around points[..=i]
+        circle = Circle::through_two(points[i], points[i]);
+        for j in 0..i {
+            if circle.contains(points[j]) {
+                continue;
+            }
+            circle = Circle::through_two(points[i], points[j]);
+            for k in 0..j {
+                if !circle.contains(points[k]) {
+                    circle = Circle::through_three(points[i], points[j], points[k]);
+                }
+            }
+        }
+    }
+    Some(circle)
+}
+
+/// Counts the pixel edges of a region that border a cell outside of it.
+pub fn perimeter(points: &HashSet<Point>) -> usize {
+    points
+        .iter()
+        .map(|p| p.neighbors().iter().filter(|n| !points.contains(n)).count())
+        .sum()
+}
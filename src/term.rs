@@ -0,0 +1,147 @@
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+// cleared by --no-color
+static COLOR_ALLOWED: AtomicBool = AtomicBool::new(true);
+
+/// Turns ANSI coloring off (or back on) for everything this crate prints.
+pub fn set_color_allowed(allowed: bool) {
+    COLOR_ALLOWED.store(allowed, Ordering::Relaxed);
+}
+
+/// Whether output should be colored: only when the human-readable report is
+/// going to a terminal, coloring hasn't been turned off, and `NO_COLOR` isn't
+/// set to anything non-empty. Every ANSI escape goes through here.
+pub fn color_enabled() -> bool {
+    let terminal = if human_to_stderr() {
+        std::io::stderr().is_terminal()
+    } else {
+        std::io::stdout().is_terminal()
+    };
+    COLOR_ALLOWED.load(Ordering::Relaxed)
+        && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+        && terminal
+}
+
+fn paint(text: &str, code: &str) -> String {
+    if color_enabled() {
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
+
+pub fn green(text: &str) -> String {
+    paint(text, "32")
+}
+
+pub fn red(text: &str) -> String {
+    paint(text, "31")
+}
+
+// set by --deterministic
+static DETERMINISTIC: AtomicBool = AtomicBool::new(false);
+
+/// Makes everything printed come out in the same order on every run over
+/// the same log, sorting wherever a map would otherwise be printed in its
+/// (randomized) iteration order.
+pub fn set_deterministic(deterministic: bool) {
+    DETERMINISTIC.store(deterministic, Ordering::Relaxed);
+}
+
+pub fn deterministic() -> bool {
+    DETERMINISTIC.load(Ordering::Relaxed)
+}
+
+// set while stdout carries machine-readable output
+static HUMAN_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Sends the human-readable report to stderr instead of stdout, so stdout
+/// can carry machine-readable output on its own.
+pub fn set_human_to_stderr(to_stderr: bool) {
+    HUMAN_TO_STDERR.store(to_stderr, Ordering::Relaxed);
+}
+
+pub fn human_to_stderr() -> bool {
+    HUMAN_TO_STDERR.load(Ordering::Relaxed)
+}
+
+/// `println!` for the human-readable report, which goes to stderr instead
+/// while stdout carries machine-readable output.
+#[macro_export]
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if $crate::term::human_to_stderr() {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
+// set by --json-pretty or --json-compact; `None` follows stdout
+static JSON_PRETTY: Mutex<Option<bool>> = Mutex::new(None);
+
+/// Pretty-prints JSON output (`Some(true)`), keeps it compact
+/// (`Some(false)`), or leaves it to `json_pretty`'s default (`None`).
+pub fn set_json_pretty(pretty: Option<bool>) {
+    *JSON_PRETTY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = pretty;
+}
+
+/// Whether JSON should be pretty-printed: as asked with `set_json_pretty`,
+/// or otherwise only when stdout is a terminal, where a person reads it
+/// rather than a pipe.
+pub fn json_pretty() -> bool {
+    let pretty = *JSON_PRETTY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    pretty.unwrap_or_else(|| std::io::stdout().is_terminal())
+}
+
+// set by --by-severity: warnings wait for the end instead of printing
+static HOLD_WARNINGS: AtomicBool = AtomicBool::new(false);
+static WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Holds warnings back until `take_warnings`, instead of printing each one
+/// to stderr as it comes up.
+pub fn set_hold_warnings(hold: bool) {
+    HOLD_WARNINGS.store(hold, Ordering::Relaxed);
+}
+
+pub fn holding_warnings() -> bool {
+    HOLD_WARNINGS.load(Ordering::Relaxed)
+}
+
+/// Prints a warning to stderr, or keeps it for later while warnings are held.
+/// Use `warn!` rather than calling this directly.
+pub fn warning(message: String) {
+    if holding_warnings() {
+        WARNINGS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(message);
+    } else {
+        eprintln!("Warning: {}", message);
+    }
+}
+
+/// The warnings held back so far, oldest first, leaving none held.
+pub fn take_warnings() -> Vec<String> {
+    std::mem::take(
+        &mut *WARNINGS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()),
+    )
+}
+
+/// `eprintln!` for warnings, prefixed with `Warning: `; with `--by-severity`
+/// they are saved for the warnings section at the end instead.
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        $crate::term::warning(format!($($arg)*))
+    };
+}
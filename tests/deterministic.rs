@@ -0,0 +1,33 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+// one run over the fixture, returning its output and the --dump-map file
+fn run(dump_map: &Path) -> (Output, Vec<u8>) {
+    let output = Command::new(env!("CARGO_BIN_EXE_log-reader"))
+        .arg(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/overlaps.log"
+        ))
+        .args(["--deterministic", "--stats", "--color-stats", "--dump-map"])
+        .arg(dump_map)
+        .output()
+        .expect("the binary runs");
+    let map = fs::read(dump_map).expect("--dump-map wrote its file");
+    (output, map)
+}
+
+#[test]
+fn deterministic_runs_match() {
+    let dump_map = std::env::temp_dir().join(format!("log-reader-{}.json", std::process::id()));
+    let (first, first_map) = run(&dump_map);
+    let (second, second_map) = run(&dump_map);
+    fs::remove_file(&dump_map).ok();
+
+    // the fixture has overlaps and duplicated patterns, so the checks fail
+    assert_eq!(first.status.code(), Some(1));
+    assert_eq!(first.status, second.status);
+    assert_eq!(first.stdout, second.stdout);
+    assert_eq!(first.stderr, second.stderr);
+    assert_eq!(first_map, second_map);
+}
@@ -0,0 +1,60 @@
+use std::process::{Command, Output};
+
+// one run over a log with each artist's pixels in opposite corners of the
+// i16 range, with `command` before the log's path and `args` after it
+fn run(command: &[&str], args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_log-reader"))
+        .args(command)
+        .arg(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/spread.log"
+        ))
+        .args(args)
+        .output()
+        .expect("the binary runs")
+}
+
+#[test]
+fn holes_on_a_spread_out_artist() {
+    let output = run(&[], &["--check-holes", "--only", "holes"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(0), "{}", stdout);
+    assert!(
+        stdout.contains("No artist regions have holes!"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn compare_spread_out_artists() {
+    let output = run(
+        &["compare-artists"],
+        &["140001", "140002", "--render", "/dev/null", "/dev/null"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // the match is still named, only the drawings are refused
+    assert!(
+        stdout.contains("rotated 90 degrees clockwise"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("drawn as text"), "{}", stdout);
+    assert_eq!(output.status.code(), Some(1), "{}", stderr);
+    assert!(stderr.contains("more than the 16777216"), "{}", stderr);
+}
+
+#[test]
+fn default_run_on_spread_out_artists() {
+    let output = run(&[], &["--deterministic"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // too few artists, so the checks fail, but every one of them runs
+    assert_eq!(output.status.code(), Some(1), "{}", stderr);
+    assert!(!stderr.contains("panicked"), "{}", stderr);
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("No duplicate patterns found!"),
+        "{}",
+        stderr
+    );
+}